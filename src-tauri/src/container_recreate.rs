@@ -0,0 +1,392 @@
+use bollard::models::{
+    ContainerConfig,
+    ContainerCreateBody,
+    ContainerInspectResponse,
+    EndpointSettings,
+    HostConfig,
+    ImageConfig,
+    Mount,
+    MountPointTypeEnum,
+    MountTypeEnum,
+    NetworkConnectRequest,
+    NetworkingConfig,
+};
+use bollard::query_parameters::{
+    CreateContainerOptions,
+    InspectContainerOptions,
+    RemoveContainerOptionsBuilder,
+    RenameContainerOptions,
+    StartContainerOptionsBuilder,
+    StopContainerOptionsBuilder,
+};
+use bollard::Docker;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::{get_docker_connection, DockerConnection};
+
+// How long a restarted container has to stay up before the old one is removed.
+const START_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecreateContainerResult {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub image_id: String,
+    pub previous_image_id: String,
+    pub started: bool,
+    // Set when the old container could not be removed and was left behind under this name.
+    pub previous_container: Option<String>,
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+fn unless_default<T: PartialEq>(value: Option<T>, default: Option<&T>) -> Option<T> {
+    match (value, default) {
+        (Some(value), Some(default)) if value == *default => None,
+        (value, _) => value,
+    }
+}
+
+fn without_defaults<V: PartialEq>(
+    map: Option<HashMap<String, V>>,
+    defaults: Option<&HashMap<String, V>>
+) -> Option<HashMap<String, V>> {
+    let defaults = match defaults {
+        Some(defaults) => defaults,
+        None => return map,
+    };
+
+    map.map(|map| map.into_iter()
+        .filter(|(key, value)| defaults.get(key) != Some(value))
+        .collect())
+}
+
+// Inspect returns the container config merged with the image defaults. Values that still
+// equal the old image's defaults are dropped, so the new image's defaults take effect.
+fn create_body(config: ContainerConfig, image: Option<ImageConfig>, container_id: &str) -> ContainerCreateBody {
+    let image = image.unwrap_or_default();
+
+    let env = config.env.map(|env| {
+        let defaults = image.env.clone().unwrap_or_default();
+        env.into_iter().filter(|entry| !defaults.contains(entry)).collect()
+    });
+
+    // The Engine clears the image CMD when an entrypoint is given, so keep both together.
+    let entrypoint = unless_default(config.entrypoint, image.entrypoint.as_ref());
+    let cmd = if entrypoint.is_some() {
+        config.cmd
+    } else {
+        unless_default(config.cmd, image.cmd.as_ref())
+    };
+
+    // Unless set explicitly, the hostname is the short container id.
+    let hostname = config.hostname
+        .filter(|hostname| hostname.as_str() != short_id(container_id));
+
+    ContainerCreateBody {
+        hostname,
+        domainname: config.domainname,
+        user: unless_default(config.user, image.user.as_ref()),
+        attach_stdin: config.attach_stdin,
+        attach_stdout: config.attach_stdout,
+        attach_stderr: config.attach_stderr,
+        exposed_ports: without_defaults(config.exposed_ports, image.exposed_ports.as_ref()),
+        tty: config.tty,
+        open_stdin: config.open_stdin,
+        stdin_once: config.stdin_once,
+        env,
+        cmd,
+        healthcheck: unless_default(config.healthcheck, image.healthcheck.as_ref()),
+        args_escaped: config.args_escaped,
+        image: None,
+        volumes: without_defaults(config.volumes, image.volumes.as_ref()),
+        working_dir: unless_default(config.working_dir, image.working_dir.as_ref()),
+        entrypoint,
+        network_disabled: config.network_disabled,
+        mac_address: None,
+        on_build: None,
+        labels: without_defaults(config.labels, image.labels.as_ref()),
+        stop_signal: unless_default(config.stop_signal, image.stop_signal.as_ref()),
+        stop_timeout: config.stop_timeout,
+        shell: unless_default(config.shell, image.shell.as_ref()),
+        host_config: None,
+        networking_config: None,
+    }
+}
+
+fn bind_target(bind: &str) -> Option<&str> {
+    bind.split(':').nth(1)
+}
+
+// Inspect reports links as `/db:/web/db`, create expects `db:db`.
+fn create_link(link: &str) -> String {
+    match link.split_once(':') {
+        Some((target, alias)) => format!(
+            "{}:{}",
+            target.trim_start_matches('/'),
+            alias.rsplit('/').next().unwrap_or(alias)
+        ),
+        None => link.trim_start_matches('/').to_string(),
+    }
+}
+
+fn create_host_config(container: &ContainerInspectResponse) -> HostConfig {
+    let mut host_config = container.host_config.clone().unwrap_or_default();
+
+    host_config.links = host_config.links
+        .map(|links| links.iter().map(|link| create_link(link)).collect());
+
+    // Anonymous volumes (image VOLUMEs and bare `-v /path`) only show up in the mounts,
+    // carry them over explicitly so the data follows the container.
+    let covered: Vec<String> = host_config.binds.iter().flatten()
+        .filter_map(|bind| bind_target(bind).map(str::to_string))
+        .chain(host_config.mounts.iter().flatten().filter_map(|mount| mount.target.clone()))
+        .collect();
+
+    let anonymous: Vec<Mount> = container.mounts.iter().flatten()
+        .filter(|mount| mount.typ == Some(MountPointTypeEnum::VOLUME))
+        .filter(|mount| mount.destination.as_ref().is_some_and(|d| !covered.contains(d)))
+        .map(|mount| Mount {
+            target: mount.destination.clone(),
+            source: mount.name.clone(),
+            typ: Some(MountTypeEnum::VOLUME),
+            read_only: Some(!mount.rw.unwrap_or(true)),
+            ..Default::default()
+        })
+        .collect();
+
+    if !anonymous.is_empty() {
+        host_config.mounts.get_or_insert_with(Vec::new).extend(anonymous);
+    }
+
+    host_config
+}
+
+// Only user supplied endpoint settings, the Engine fills in addresses and ids on its own.
+fn endpoint_settings(endpoint: &EndpointSettings, container_id: &str) -> EndpointSettings {
+    EndpointSettings {
+        ipam_config: endpoint.ipam_config.clone(),
+        links: endpoint.links.clone(),
+        aliases: endpoint.aliases.as_ref().map(|aliases| aliases.iter()
+            .filter(|alias| alias.as_str() != short_id(container_id))
+            .cloned()
+            .collect()),
+        driver_opts: endpoint.driver_opts.clone(),
+        gw_priority: endpoint.gw_priority,
+        ..Default::default()
+    }
+}
+
+// `--rm` containers are deleted by the Engine as soon as they stop, together with their
+// anonymous volumes, so there would be nothing left to roll back to.
+fn check_recreatable(container: &ContainerInspectResponse) -> Result<(), String> {
+    let auto_remove = container.host_config.as_ref()
+        .and_then(|hc| hc.auto_remove)
+        .unwrap_or(false);
+    if auto_remove {
+        return Err("Container was started with --rm and would be deleted when stopped, recreate it manually".to_string());
+    }
+
+    Ok(())
+}
+
+async fn rollback(
+    docker: &Docker,
+    new_id: Option<&str>,
+    old_id: &str,
+    name: &str,
+    was_running: bool,
+    reason: String
+) -> String {
+    let mut errors = Vec::new();
+
+    if let Some(new_id) = new_id {
+        let options = RemoveContainerOptionsBuilder::default().force(true).build();
+        if let Err(e) = docker.remove_container(new_id, Some(options)).await {
+            errors.push(format!("failed to remove new container: {}", e));
+        }
+    }
+
+    let rename = RenameContainerOptions { name: name.to_string() };
+    if let Err(e) = docker.rename_container(old_id, rename).await {
+        errors.push(format!("failed to restore name: {}", e));
+    }
+
+    if was_running {
+        let options = StartContainerOptionsBuilder::default().build();
+        if let Err(e) = docker.start_container(old_id, Some(options)).await {
+            errors.push(format!("failed to restart old container: {}", e));
+        }
+    }
+
+    if errors.is_empty() {
+        format!("{}, the original container was restored", reason)
+    } else {
+        format!("{}, rollback incomplete: {}", reason, errors.join("; "))
+    }
+}
+
+#[tauri::command]
+pub async fn recreate_container(
+    id: String,
+    new_image: Option<String>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<RecreateContainerResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let container = docker.inspect_container(&id, None::<InspectContainerOptions>).await
+        .map_err(|e| format!("Failed to inspect container: {}", e))?;
+    check_recreatable(&container)?;
+
+    let old_id = container.id.clone().unwrap_or_else(|| id.clone());
+    let name = container.name.clone().unwrap_or_default().trim_start_matches('/').to_string();
+    let config = container.config.clone().unwrap_or_default();
+    let previous_image_id = container.image.clone().unwrap_or_default();
+    let was_running = container.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+
+    let image = match new_image.filter(|image| !image.trim().is_empty()) {
+        Some(image) => image.trim().to_string(),
+        None => config.image.clone()
+            .ok_or_else(|| "Container has no image reference".to_string())?,
+    };
+
+    let image_id = docker.inspect_image(&image).await
+        .map_err(|e| format!("Image {} is not available locally: {}", image, e))?
+        .id
+        .unwrap_or_default();
+
+    let old_image_config = docker.inspect_image(&previous_image_id).await
+        .ok()
+        .and_then(|image| image.config);
+
+    let mut body = create_body(config, old_image_config, &old_id);
+    body.image = Some(image.clone());
+    body.host_config = Some(create_host_config(&container));
+
+    let network_mode = body.host_config.as_ref()
+        .and_then(|hc| hc.network_mode.clone())
+        .unwrap_or_else(|| "default".to_string());
+    let primary_network = if network_mode == "default" { "bridge".to_string() } else { network_mode };
+
+    // Older Engines accept a single endpoint at create time, the rest are connected afterwards.
+    let mut extra_networks = Vec::new();
+    let networks = container.network_settings.as_ref()
+        .and_then(|ns| ns.networks.clone())
+        .unwrap_or_default();
+    for (network, endpoint) in &networks {
+        let settings = endpoint_settings(endpoint, &old_id);
+        if *network == primary_network {
+            body.networking_config = Some(NetworkingConfig {
+                endpoints_config: Some(HashMap::from([(network.clone(), settings)])),
+            });
+        } else {
+            extra_networks.push((network.clone(), settings));
+        }
+    }
+
+    // Renaming first keeps the old container in place under its backup name whatever happens next.
+    let backup_name = format!("{}-old-{}", name, Utc::now().timestamp());
+    docker.rename_container(&old_id, RenameContainerOptions { name: backup_name.clone() }).await
+        .map_err(|e| format!("Failed to rename container: {}", e))?;
+
+    if was_running {
+        let options = StopContainerOptionsBuilder::default().build();
+        if let Err(e) = docker.stop_container(&old_id, Some(options)).await {
+            // Still running, so only the name needs restoring.
+            let reason = format!("Failed to stop container: {}", e);
+            return Err(rollback(&docker, None, &old_id, &name, false, reason).await);
+        }
+    }
+
+    let options = CreateContainerOptions {
+        name: Some(name.clone()),
+        ..Default::default()
+    };
+    let new_id = match docker.create_container(Some(options), body).await {
+        Ok(response) => response.id,
+        Err(e) => {
+            let reason = format!("Failed to create container: {}", e);
+            return Err(rollback(&docker, None, &old_id, &name, was_running, reason).await);
+        },
+    };
+
+    for (network, settings) in extra_networks {
+        let request = NetworkConnectRequest {
+            container: Some(new_id.clone()),
+            endpoint_config: Some(settings),
+        };
+        if let Err(e) = docker.connect_network(&network, request).await {
+            let reason = format!("Failed to connect to network {}: {}", network, e);
+            return Err(rollback(&docker, Some(&new_id), &old_id, &name, was_running, reason).await);
+        }
+    }
+
+    if was_running {
+        let options = StartContainerOptionsBuilder::default().build();
+        if let Err(e) = docker.start_container(&new_id, Some(options)).await {
+            let reason = format!("Failed to start container: {}", e);
+            return Err(rollback(&docker, Some(&new_id), &old_id, &name, was_running, reason).await);
+        }
+
+        // Catch containers that start and crash right away, e.g. on a config the new image rejects.
+        tokio::time::sleep(START_GRACE_PERIOD).await;
+        let started = match docker.inspect_container(&new_id, None::<InspectContainerOptions>).await {
+            Ok(started) => started,
+            Err(e) => {
+                let reason = format!("Failed to inspect container: {}", e);
+                return Err(rollback(&docker, Some(&new_id), &old_id, &name, was_running, reason).await);
+            },
+        };
+        let state = started.state.unwrap_or_default();
+        if !state.running.unwrap_or(false) && state.exit_code.unwrap_or(0) != 0 {
+            let reason = format!("Container exited with code {}", state.exit_code.unwrap_or(0));
+            return Err(rollback(&docker, Some(&new_id), &old_id, &name, was_running, reason).await);
+        }
+    }
+
+    // Keep anonymous volumes, they are mounted into the new container.
+    let options = RemoveContainerOptionsBuilder::default().v(false).build();
+    let previous_container = match docker.remove_container(&old_id, Some(options)).await {
+        Ok(_) => None,
+        Err(_) => Some(backup_name),
+    };
+
+    Ok(RecreateContainerResult {
+        id: short_id(&new_id).to_string(),
+        name,
+        image,
+        image_id,
+        previous_image_id,
+        started: was_running,
+        previous_container,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(auto_remove: Option<bool>) -> ContainerInspectResponse {
+        ContainerInspectResponse {
+            host_config: Some(HostConfig {
+                auto_remove,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn refuses_auto_remove_containers() {
+        assert!(check_recreatable(&container(Some(true))).is_err());
+        assert!(check_recreatable(&container(Some(false))).is_ok());
+        assert!(check_recreatable(&container(None)).is_ok());
+        assert!(check_recreatable(&ContainerInspectResponse::default()).is_ok());
+    }
+}
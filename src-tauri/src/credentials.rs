@@ -0,0 +1,432 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bollard::auth::DockerCredentials;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::registry::{RegistryAuth, RegistryClient};
+
+pub const DOCKER_HUB_REGISTRY: &str = "docker.io";
+const DOCKER_HUB_CONFIG_KEY: &str = "https://index.docker.io/v1/";
+const KEYRING_SERVICE: &str = "dockpit-registry";
+
+#[derive(Deserialize, Debug, Default)]
+struct DockerConfigFile {
+    #[serde(default)]
+    auths: HashMap<String, DockerConfigAuth>,
+    #[serde(default, rename = "credHelpers")]
+    cred_helpers: HashMap<String, String>,
+    #[serde(rename = "credsStore")]
+    creds_store: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct DockerConfigAuth {
+    auth: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    identitytoken: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct HelperCredentials {
+    #[serde(rename = "Username")]
+    username: String,
+    #[serde(rename = "Secret")]
+    secret: String,
+}
+
+// Only the registry/username pairs live on disk, passwords go to the OS keyring.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredLogin {
+    registry: String,
+    username: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistryLogin {
+    pub registry: String,
+    pub username: Option<String>,
+    pub source: String,
+}
+
+// `ghcr.io/org/app` -> (ghcr.io, org/app), `index.docker.io/library/nginx` -> (docker.io, library/nginx)
+pub fn split_registry(reference: &str) -> (String, &str) {
+    match reference.split_once('/') {
+        Some((first, rest)) if first.contains('.') || first.contains(':') || first == "localhost" => {
+            (normalize_registry(first), rest)
+        },
+        _ => (DOCKER_HUB_REGISTRY.to_string(), reference),
+    }
+}
+
+// `nginx`, `library/nginx` -> docker.io, `ghcr.io/org/app` -> ghcr.io
+pub fn registry_host(reference: &str) -> String {
+    split_registry(reference).0
+}
+
+// config.json keys are often full URLs (`https://index.docker.io/v1/`), compare hosts only.
+pub fn normalize_registry(key: &str) -> String {
+    let without_scheme = key
+        .strip_prefix("https://")
+        .or_else(|| key.strip_prefix("http://"))
+        .unwrap_or(key);
+    let host = without_scheme.split('/').next().unwrap_or(without_scheme);
+
+    match host {
+        "index.docker.io" | "registry-1.docker.io" => DOCKER_HUB_REGISTRY.to_string(),
+        other => other.to_string(),
+    }
+}
+
+// The address credential helpers and the Engine expect for a registry.
+fn server_address(registry: &str) -> String {
+    if registry == DOCKER_HUB_REGISTRY {
+        DOCKER_HUB_CONFIG_KEY.to_string()
+    } else {
+        registry.to_string()
+    }
+}
+
+fn docker_config_path() -> Option<PathBuf> {
+    let dir = match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()?.join(".docker"),
+    };
+    Some(dir.join("config.json"))
+}
+
+fn read_docker_config() -> DockerConfigFile {
+    docker_config_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn decode_config_auth(registry: &str, entry: &DockerConfigAuth) -> Option<DockerCredentials> {
+    let (username, password) = match entry.auth {
+        Some(ref auth) => {
+            let decoded = STANDARD.decode(auth).ok()?;
+            let decoded = String::from_utf8(decoded).ok()?;
+            let (user, pass) = decoded.split_once(':')?;
+            (Some(user.to_string()), Some(pass.to_string()))
+        },
+        None => (entry.username.clone(), entry.password.clone()),
+    };
+
+    if username.is_none() && entry.identitytoken.is_none() {
+        return None;
+    }
+
+    Some(DockerCredentials {
+        username,
+        password,
+        identitytoken: entry.identitytoken.clone(),
+        serveraddress: Some(server_address(registry)),
+        ..Default::default()
+    })
+}
+
+// Runs `docker-credential-<helper> get` the same way the docker CLI does.
+fn helper_credentials(helper: &str, registry: &str) -> Option<DockerCredentials> {
+    let server = server_address(registry);
+
+    let mut child = Command::new(format!("docker-credential-{}", helper))
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    child.stdin.take()?.write_all(server.as_bytes()).ok()?;
+
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let found: HelperCredentials = serde_json::from_slice(&output.stdout).ok()?;
+
+    // Helpers report identity tokens with this placeholder user name.
+    if found.username == "<token>" {
+        Some(DockerCredentials {
+            identitytoken: Some(found.secret),
+            serveraddress: Some(server),
+            ..Default::default()
+        })
+    } else {
+        Some(DockerCredentials {
+            username: Some(found.username),
+            password: Some(found.secret),
+            serveraddress: Some(server),
+            ..Default::default()
+        })
+    }
+}
+
+fn stored_logins_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("dockpit").join("registries.json"))
+}
+
+fn read_stored_logins() -> Vec<StoredLogin> {
+    stored_logins_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_stored_logins(logins: &[StoredLogin]) -> Result<(), String> {
+    let path = stored_logins_path()
+        .ok_or_else(|| "Cannot determine config directory".to_string())?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let content = serde_json::to_string_pretty(logins)
+        .map_err(|e| format!("Failed to serialize logins: {}", e))?;
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+async fn blocking<T: Send + 'static>(task: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Credential task failed: {}", e))?
+}
+
+fn keyring_entry(registry: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, registry)
+        .map_err(|e| format!("Failed to open keyring: {}", e))
+}
+
+fn dockpit_credentials(registry: &str) -> Option<DockerCredentials> {
+    let login = read_stored_logins().into_iter().find(|l| l.registry == registry)?;
+    let password = keyring_entry(registry).ok()?.get_password().ok()?;
+
+    Some(DockerCredentials {
+        username: Some(login.username),
+        password: Some(password),
+        serveraddress: Some(server_address(registry)),
+        ..Default::default()
+    })
+}
+
+// Lookup order: logins made in Dockpit, then config.json the way the docker CLI reads it
+// (per-registry credHelpers, inline auths, global credsStore).
+fn lookup_credentials(registry: &str) -> Option<DockerCredentials> {
+    if let Some(credentials) = dockpit_credentials(registry) {
+        return Some(credentials);
+    }
+
+    let config = read_docker_config();
+
+    if let Some((_, helper)) = config.cred_helpers.iter().find(|(key, _)| normalize_registry(key) == registry) {
+        if let Some(credentials) = helper_credentials(helper, registry) {
+            return Some(credentials);
+        }
+    }
+
+    let inline = config.auths.iter()
+        .filter(|(key, _)| normalize_registry(key) == registry)
+        .find_map(|(_, entry)| decode_config_auth(registry, entry));
+    if inline.is_some() {
+        return inline;
+    }
+
+    config.creds_store.as_deref()
+        .and_then(|store| helper_credentials(store, registry))
+}
+
+// Credential helpers and the keyring block, and may wait on a keychain prompt.
+pub async fn credentials_for_registry(registry: &str) -> Option<DockerCredentials> {
+    let registry = registry.to_string();
+    blocking(move || Ok(lookup_credentials(&registry))).await.ok().flatten()
+}
+
+pub async fn credentials_for(reference: &str) -> Option<DockerCredentials> {
+    credentials_for_registry(&registry_host(reference)).await
+}
+
+// For talking to a registry directly. Identity tokens would need the OAuth refresh flow,
+// so those logins fall back to anonymous access.
+pub async fn registry_auth_for(registry: &str) -> Option<RegistryAuth> {
+    let credentials = credentials_for_registry(registry).await?;
+
+    Some(RegistryAuth {
+        username: credentials.username?,
+        password: credentials.password?,
+    })
+}
+
+#[tauri::command]
+pub async fn registry_login(
+    registry: String,
+    username: String,
+    password: String
+) -> Result<RegistryLogin, String> {
+    let registry = normalize_registry(&registry);
+
+    let client = RegistryClient::new(&registry, Some(RegistryAuth {
+        username: username.clone(),
+        password: password.clone(),
+    }))?;
+    client.check_login().await?;
+
+    let keyring_registry = registry.clone();
+    blocking(move || keyring_entry(&keyring_registry)?
+        .set_password(&password)
+        .map_err(|e| format!("Failed to store password in keyring: {}", e))).await?;
+
+    let mut logins = read_stored_logins();
+    logins.retain(|l| l.registry != registry);
+    logins.push(StoredLogin {
+        registry: registry.clone(),
+        username: username.clone(),
+    });
+    write_stored_logins(&logins)?;
+
+    Ok(RegistryLogin {
+        registry,
+        username: Some(username),
+        source: "dockpit".to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn registry_logout(
+    registry: String
+) -> Result<(), String> {
+    let registry = normalize_registry(&registry);
+
+    let mut logins = read_stored_logins();
+    let before = logins.len();
+    logins.retain(|l| l.registry != registry);
+
+    if logins.len() == before {
+        return Err(format!("No Dockpit login stored for {}", registry));
+    }
+
+    let keyring_registry = registry.clone();
+    blocking(move || match keyring_entry(&keyring_registry)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove password from keyring: {}", e)),
+    }).await?;
+
+    write_stored_logins(&logins)
+}
+
+#[tauri::command]
+pub async fn list_registry_logins() -> Result<Vec<RegistryLogin>, String> {
+    let mut result: Vec<RegistryLogin> = read_stored_logins().into_iter()
+        .map(|l| RegistryLogin {
+            registry: l.registry,
+            username: Some(l.username),
+            source: "dockpit".to_string(),
+        })
+        .collect();
+
+    let config = read_docker_config();
+
+    for (key, helper) in &config.cred_helpers {
+        let registry = normalize_registry(key);
+        if !result.iter().any(|l| l.registry == registry) {
+            result.push(RegistryLogin {
+                registry,
+                username: None,
+                source: format!("helper:{}", helper),
+            });
+        }
+    }
+
+    for (key, entry) in &config.auths {
+        let registry = normalize_registry(key);
+        if result.iter().any(|l| l.registry == registry) {
+            continue;
+        }
+
+        // With a credsStore configured, auths entries are empty placeholders.
+        let (username, source) = match decode_config_auth(&registry, entry) {
+            Some(credentials) => (credentials.username, "docker-config".to_string()),
+            None => match config.creds_store {
+                Some(ref store) => (None, format!("helper:{}", store)),
+                None => continue,
+            },
+        };
+
+        result.push(RegistryLogin { registry, username, source });
+    }
+
+    result.sort_by(|a, b| a.registry.cmp(&b.registry));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_auth(auth: Option<&str>, username: Option<&str>, password: Option<&str>, identitytoken: Option<&str>) -> DockerConfigAuth {
+        DockerConfigAuth {
+            auth: auth.map(String::from),
+            username: username.map(String::from),
+            password: password.map(String::from),
+            identitytoken: identitytoken.map(String::from),
+        }
+    }
+
+    #[test]
+    fn finds_registry_hosts() {
+        assert_eq!(registry_host("nginx"), "docker.io");
+        assert_eq!(registry_host("library/nginx:1.25"), "docker.io");
+        assert_eq!(registry_host("ghcr.io/org/app"), "ghcr.io");
+        assert_eq!(registry_host("localhost/app"), "localhost");
+        assert_eq!(registry_host("registry:5000/app"), "registry:5000");
+        assert_eq!(registry_host("index.docker.io/library/nginx"), "docker.io");
+        assert_eq!(registry_host("registry-1.docker.io/library/nginx"), "docker.io");
+        assert_eq!(split_registry("index.docker.io/library/nginx"), ("docker.io".to_string(), "library/nginx"));
+    }
+
+    #[test]
+    fn normalizes_registries() {
+        assert_eq!(normalize_registry("https://index.docker.io/v1/"), "docker.io");
+        assert_eq!(normalize_registry("registry-1.docker.io"), "docker.io");
+        assert_eq!(normalize_registry("http://localhost:5000"), "localhost:5000");
+        assert_eq!(normalize_registry("https://ghcr.io/v2/"), "ghcr.io");
+        assert_eq!(normalize_registry("quay.io"), "quay.io");
+    }
+
+    #[test]
+    fn maps_server_addresses() {
+        assert_eq!(server_address("docker.io"), "https://index.docker.io/v1/");
+        assert_eq!(server_address("ghcr.io"), "ghcr.io");
+    }
+
+    #[test]
+    fn decodes_config_auth() {
+        let entry = config_auth(Some(&STANDARD.encode("alice:s3:cret")), None, None, None);
+        let credentials = decode_config_auth("docker.io", &entry).unwrap();
+        assert_eq!(credentials.username.as_deref(), Some("alice"));
+        assert_eq!(credentials.password.as_deref(), Some("s3:cret"));
+        assert_eq!(credentials.serveraddress.as_deref(), Some("https://index.docker.io/v1/"));
+
+        let entry = config_auth(None, Some("bob"), Some("pw"), None);
+        let credentials = decode_config_auth("ghcr.io", &entry).unwrap();
+        assert_eq!(credentials.username.as_deref(), Some("bob"));
+        assert_eq!(credentials.serveraddress.as_deref(), Some("ghcr.io"));
+
+        let entry = config_auth(None, None, None, Some("token"));
+        let credentials = decode_config_auth("acr.example.io", &entry).unwrap();
+        assert_eq!(credentials.identitytoken.as_deref(), Some("token"));
+        assert_eq!(credentials.username, None);
+
+        assert!(decode_config_auth("ghcr.io", &config_auth(Some("not base64!"), None, None, None)).is_none());
+        assert!(decode_config_auth("ghcr.io", &config_auth(Some(&STANDARD.encode("nocolon")), None, None, None)).is_none());
+        assert!(decode_config_auth("ghcr.io", &config_auth(None, None, None, None)).is_none());
+    }
+}
@@ -0,0 +1,762 @@
+use bollard::query_parameters::{DownloadFromContainerOptions, UploadToContainerOptions};
+use bollard::Docker;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use tar::EntryType;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+
+use crate::image_transfer::counted_file_stream;
+use crate::volumes::{create_volume_helper, remove_volume_helper, VOLUME_MOUNT_PATH};
+use crate::{get_docker_connection, DockerConnection};
+
+const DEFAULT_READ_LIMIT: u64 = 1024 * 1024;
+const MAX_READ_LIMIT: u64 = 16 * 1024 * 1024;
+
+// Same heuristic as git: a NUL byte early in the file means binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+// Symlinked directories (e.g. `/bin -> usr/bin`) are followed this many times.
+const MAX_LINK_HOPS: usize = 8;
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowseTarget {
+    Container,
+    Volume,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: String,
+    // "file", "dir", "symlink" or "other"
+    pub kind: String,
+    // None for directories, see get_path_size
+    pub size: Option<u64>,
+    pub mode: u32,
+    pub permissions: String,
+    pub modified: u64,
+    pub link_target: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileContent {
+    pub path: String,
+    pub size: u64,
+    pub content: Option<String>,
+    pub binary: bool,
+    pub truncated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PathSize {
+    pub path: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileTransferResult {
+    pub path: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+// The container whose archive endpoint serves the files, and where the browsed root is in it.
+struct ArchiveSource {
+    container: String,
+    root: &'static str,
+    helper: bool,
+}
+
+impl ArchiveSource {
+    async fn open(docker: &Docker, target: BrowseTarget, id: &str, writable: bool) -> Result<Self, String> {
+        match target {
+            BrowseTarget::Container => Ok(Self {
+                container: id.to_string(),
+                root: "",
+                helper: false,
+            }),
+            BrowseTarget::Volume => Ok(Self {
+                container: create_volume_helper(docker, id, !writable).await?,
+                root: VOLUME_MOUNT_PATH,
+                helper: true,
+            }),
+        }
+    }
+
+    async fn close(self, docker: &Docker) {
+        if self.helper {
+            remove_volume_helper(docker, &self.container).await;
+        }
+    }
+
+    fn path(&self, path: &str) -> String {
+        if path == "/" && !self.root.is_empty() {
+            self.root.to_string()
+        } else {
+            format!("{}{}", self.root, path)
+        }
+    }
+
+    // A blocking reader over `GET /containers/{id}/archive`, for use in spawn_blocking.
+    fn reader(&self, docker: &Docker, path: &str) -> impl Read + Send + 'static {
+        let options = DownloadFromContainerOptions {
+            path: self.path(path),
+        };
+        let stream = docker.download_from_container(&self.container, Some(options))
+            .map(|chunk| chunk.map_err(std::io::Error::other));
+
+        SyncIoBridge::new(StreamReader::new(Box::pin(stream)))
+    }
+}
+
+// `etc/../x` is rejected, so a volume path can never leave the mount.
+fn normalize_path(path: &str) -> Result<String, String> {
+    let mut parts = Vec::new();
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::RootDir | Component::CurDir => {},
+            _ => return Err(format!("Invalid path: {}", path)),
+        }
+    }
+
+    Ok(format!("/{}", parts.join("/")))
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent == "/" {
+        format!("/{}", name)
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+fn entry_kind(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::Regular | EntryType::Continuous | EntryType::Link => "file",
+        EntryType::Directory => "dir",
+        EntryType::Symlink => "symlink",
+        _ => "other",
+    }
+}
+
+// `drwxr-xr-x`
+fn permissions(kind: &str, mode: u32) -> String {
+    let prefix = match kind {
+        "dir" => 'd',
+        "symlink" => 'l',
+        _ => '-',
+    };
+
+    std::iter::once(prefix)
+        .chain((0..9).rev().map(|bit| {
+            if mode & (1 << bit) == 0 {
+                '-'
+            } else {
+                ['x', 'w', 'r'][bit % 3]
+            }
+        }))
+        .collect()
+}
+
+fn resolve_link(path: &str, target: &str) -> Result<String, String> {
+    if target.starts_with('/') {
+        return normalize_path(target);
+    }
+
+    // Relative targets may climb out of the link's directory, resolve `..` by hand.
+    let parent = Path::new(path).parent().unwrap_or(Path::new("/"));
+    let mut parts: Vec<String> = parent.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    for component in Path::new(target).components() {
+        match component {
+            Component::ParentDir => {
+                parts.pop();
+            },
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => {},
+        }
+    }
+
+    Ok(format!("/{}", parts.join("/")))
+}
+
+enum Listing {
+    Entries(Vec<FileEntry>),
+    Link(String),
+}
+
+// The Engine has no listing endpoint and the archive of a directory holds its whole
+// subtree, so only the direct children are kept and deeper entries are skipped.
+fn read_listing(reader: impl Read, path: &str) -> Result<Listing, String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = archive.entries()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let root = entries.next()
+        .ok_or_else(|| format!("{} not found", path))?
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let root_path = root.path()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .into_owned();
+
+    match root.header().entry_type() {
+        EntryType::Directory => {},
+        EntryType::Symlink => {
+            let target = root.link_name().ok().flatten()
+                .map(|t| t.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return Ok(Listing::Link(target));
+        },
+        _ => return Err(format!("{} is not a directory", path)),
+    }
+
+    let mut children: Vec<FileEntry> = Vec::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let entry_path = entry.path()
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?
+            .into_owned();
+
+        let Ok(relative) = entry_path.strip_prefix(&root_path) else {
+            continue;
+        };
+        let mut components = relative.components();
+        let (Some(Component::Normal(name)), None) = (components.next(), components.next()) else {
+            continue;
+        };
+        let name = name.to_string_lossy().to_string();
+
+        let header = entry.header();
+        let kind = entry_kind(header.entry_type());
+        let mode = header.mode().unwrap_or(0) & 0o7777;
+
+        children.push(FileEntry {
+            path: join_path(path, &name),
+            name,
+            kind: kind.to_string(),
+            size: (kind != "dir").then(|| header.size().unwrap_or(0)),
+            mode,
+            permissions: permissions(kind, mode),
+            modified: header.mtime().unwrap_or(0),
+            link_target: entry.link_name().ok().flatten().map(|t| t.to_string_lossy().to_string()),
+        });
+    }
+
+    // Directories first, then by name, like most file managers.
+    children.sort_by(|a, b| (a.kind != "dir").cmp(&(b.kind != "dir")).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Listing::Entries(children))
+}
+
+fn read_content(reader: impl Read, path: &str, limit: u64) -> Result<FileContent, String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entry = archive.entries()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .next()
+        .ok_or_else(|| format!("{} not found", path))?
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    match entry.header().entry_type() {
+        EntryType::Regular | EntryType::Continuous => {},
+        EntryType::Directory => return Err(format!("{} is a directory", path)),
+        EntryType::Symlink => {
+            let target = entry.link_name().ok().flatten()
+                .map(|t| t.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return Err(format!("{} is a symlink to {}", path, target));
+        },
+        _ => return Err(format!("{} is not a regular file", path)),
+    }
+
+    let size = entry.header().size().unwrap_or(0);
+    let mut data = Vec::new();
+    (&mut entry).take(limit)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let binary = data[..data.len().min(BINARY_SNIFF_LEN)].contains(&0);
+    let content = if binary {
+        None
+    } else {
+        // A multi-byte character may be cut at the limit, which is not a reason to call it binary.
+        match String::from_utf8(data) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                let valid = e.utf8_error().valid_up_to();
+                let bytes = e.into_bytes();
+                (bytes.len() - valid < 4 && size > limit)
+                    .then(|| String::from_utf8_lossy(&bytes[..valid]).to_string())
+            },
+        }
+    };
+
+    Ok(FileContent {
+        path: path.to_string(),
+        size,
+        binary: content.is_none(),
+        content,
+        truncated: size > limit,
+    })
+}
+
+// Walks the whole archive, which is why it is not part of the listing.
+fn read_size(reader: impl Read, path: &str) -> Result<PathSize, String> {
+    let mut archive = tar::Archive::new(reader);
+    let entries = archive.entries()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let (mut files, mut bytes) = (0u64, 0u64);
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if entry.header().entry_type().is_file() {
+            files += 1;
+            bytes += entry.header().size().unwrap_or(0);
+        }
+    }
+
+    Ok(PathSize {
+        path: path.to_string(),
+        files,
+        bytes,
+    })
+}
+
+// A symlink unpacked earlier must not redirect later entries out of the destination.
+fn check_no_symlink_ancestor(destination: &Path, relative: &Path) -> Result<(), String> {
+    let mut current = destination.to_path_buf();
+
+    for component in relative.parent().map(|p| p.components()).into_iter().flatten() {
+        current.push(component);
+        if std::fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(format!("Refusing to write below symlink {}", current.display()));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_plain_relative(path: &Path) -> bool {
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+// Written to `destination`, or below it under the downloaded path's own name when it is
+// an existing directory. Returns where the download ended up.
+fn extract_download(reader: impl Read, path: &str, destination: &Path) -> Result<(PathBuf, u64, u64), String> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = archive.entries()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut root = entries.next()
+        .ok_or_else(|| format!("{} not found", path))?
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let root_path = root.path()
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .into_owned();
+
+    let destination = match root_path.file_name() {
+        Some(name) if destination.is_dir() => destination.join(name),
+        _ => destination.to_path_buf(),
+    };
+
+    if root.header().entry_type() != EntryType::Directory {
+        let size = root.header().size().unwrap_or(0);
+        root.unpack(&destination)
+            .map_err(|e| format!("Failed to write {}: {}", destination.display(), e))?;
+        return Ok((destination, 1, size));
+    }
+    let destination = destination.as_path();
+
+    std::fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+
+    let (mut files, mut bytes) = (0u64, 0u64);
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let entry_path = entry.path()
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?
+            .into_owned();
+
+        let Ok(relative) = entry_path.strip_prefix(&root_path) else {
+            continue;
+        };
+        if !is_plain_relative(relative) {
+            continue;
+        }
+
+        check_no_symlink_ancestor(destination, relative)?;
+        let target = destination.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        // Hardlink targets are archive paths. Unpacking them as-is would resolve them
+        // against the working directory, so they are linked inside the destination here.
+        if entry.header().entry_type() == EntryType::Link {
+            let link = entry.link_name()
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?
+                .ok_or_else(|| format!("Hardlink without target in {}", path))?
+                .into_owned();
+            let link_relative = link.strip_prefix(&root_path).ok()
+                .filter(|l| is_plain_relative(l))
+                .ok_or_else(|| format!("Hardlink {} points outside {}", link.display(), path))?;
+            check_no_symlink_ancestor(destination, link_relative)?;
+
+            let _ = std::fs::remove_file(&target);
+            std::fs::hard_link(destination.join(link_relative), &target)
+                .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+            files += 1;
+            continue;
+        }
+
+        if entry.header().entry_type().is_file() {
+            files += 1;
+            bytes += entry.header().size().unwrap_or(0);
+        }
+        entry.unpack(&target)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+
+    Ok((destination.to_path_buf(), files, bytes))
+}
+
+async fn blocking<T: Send + 'static>(task: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Archive task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn list_files(
+    target: BrowseTarget,
+    id: String,
+    path: Option<String>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<Vec<FileEntry>, String> {
+    let docker = get_docker_connection(state)?;
+    let mut path = normalize_path(path.as_deref().unwrap_or("/"))?;
+
+    let source = ArchiveSource::open(&docker, target, &id, false).await?;
+
+    let result = async {
+        for _ in 0..=MAX_LINK_HOPS {
+            let reader = source.reader(&docker, &path);
+            let current = path.clone();
+
+            match blocking(move || read_listing(reader, &current)).await? {
+                Listing::Entries(entries) => return Ok(entries),
+                Listing::Link(target) => path = resolve_link(&path, &target)?,
+            }
+        }
+        Err(format!("Too many levels of symbolic links: {}", path))
+    }.await;
+
+    source.close(&docker).await;
+    result
+}
+
+// Total size of a file or directory, computed on demand since it reads the whole subtree.
+#[tauri::command]
+pub async fn get_path_size(
+    target: BrowseTarget,
+    id: String,
+    path: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<PathSize, String> {
+    let docker = get_docker_connection(state)?;
+    let path = normalize_path(&path)?;
+
+    let source = ArchiveSource::open(&docker, target, &id, false).await?;
+
+    let reader = source.reader(&docker, &path);
+    let current = path.clone();
+    let result = blocking(move || read_size(reader, &current)).await;
+
+    source.close(&docker).await;
+    result
+}
+
+#[tauri::command]
+pub async fn read_file(
+    target: BrowseTarget,
+    id: String,
+    path: String,
+    max_bytes: Option<u64>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<FileContent, String> {
+    let docker = get_docker_connection(state)?;
+    let path = normalize_path(&path)?;
+    let limit = max_bytes.unwrap_or(DEFAULT_READ_LIMIT).clamp(1, MAX_READ_LIMIT);
+
+    let source = ArchiveSource::open(&docker, target, &id, false).await?;
+
+    let reader = source.reader(&docker, &path);
+    let current = path.clone();
+    let result = blocking(move || read_content(reader, &current, limit)).await;
+
+    source.close(&docker).await;
+    result
+}
+
+#[tauri::command]
+pub async fn download_path(
+    target: BrowseTarget,
+    id: String,
+    path: String,
+    destination: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<FileTransferResult, String> {
+    let docker = get_docker_connection(state)?;
+    let path = normalize_path(&path)?;
+
+    let source = ArchiveSource::open(&docker, target, &id, false).await?;
+
+    let reader = source.reader(&docker, &path);
+    let current = path.clone();
+    let output = PathBuf::from(&destination);
+    let result = blocking(move || extract_download(reader, &current, &output)).await;
+
+    source.close(&docker).await;
+    let (written, files, bytes) = result?;
+
+    Ok(FileTransferResult {
+        path: written.to_string_lossy().to_string(),
+        files,
+        bytes,
+    })
+}
+
+// Uploads a local file or directory into the directory `path`. Works on stopped
+// containers too, but not on read-only root filesystems.
+#[tauri::command]
+pub async fn upload_path(
+    target: BrowseTarget,
+    id: String,
+    source: String,
+    path: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<FileTransferResult, String> {
+    let docker = get_docker_connection(state)?;
+    let path = normalize_path(&path)?;
+
+    let local = PathBuf::from(&source);
+    let name = local.file_name()
+        .ok_or_else(|| format!("Invalid source path: {}", source))?
+        .to_os_string();
+
+    let name_in_target = name.to_string_lossy().to_string();
+
+    // The archive API takes a tar, so the upload is packed into a temp file first.
+    let (archive, files, bytes) = blocking(move || {
+        let file = tempfile::NamedTempFile::new()
+            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        let mut builder = tar::Builder::new(file.reopen()
+            .map_err(|e| format!("Failed to open temp file: {}", e))?);
+        builder.follow_symlinks(false);
+
+        let metadata = std::fs::metadata(&local)
+            .map_err(|e| format!("Failed to read {}: {}", local.display(), e))?;
+
+        let (files, bytes) = if metadata.is_dir() {
+            builder.append_dir_all(&name, &local)
+                .map_err(|e| format!("Failed to archive {}: {}", local.display(), e))?;
+            tree_totals(&local)?
+        } else {
+            builder.append_path_with_name(&local, &name)
+                .map_err(|e| format!("Failed to archive {}: {}", local.display(), e))?;
+            (1, metadata.len())
+        };
+
+        builder.into_inner()
+            .map_err(|e| format!("Failed to archive {}: {}", local.display(), e))?;
+
+        Ok((file, files, bytes))
+    }).await?;
+
+    let remote = ArchiveSource::open(&docker, target, &id, true).await?;
+
+    let result = async {
+        let archive_path = archive.path().to_string_lossy().to_string();
+        let (body, _, _) = counted_file_stream(&archive_path).await?;
+
+        let options = UploadToContainerOptions {
+            path: remote.path(&path),
+            ..Default::default()
+        };
+
+        docker.upload_to_container(&remote.container, Some(options), bollard::body_try_stream(body))
+            .await
+            .map_err(|e| format!("Failed to upload {}: {}", source, e))
+    }.await;
+
+    remote.close(&docker).await;
+    result?;
+
+    Ok(FileTransferResult {
+        path: join_path(&path, &name_in_target),
+        files,
+        bytes,
+    })
+}
+
+// Number and total size of the regular files below `dir`, for the upload summary.
+fn tree_totals(dir: &Path) -> Result<(u64, u64), String> {
+    let (mut files, mut bytes) = (0u64, 0u64);
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                files += 1;
+                bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+    }
+
+    Ok((files, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Names are written into the header as-is, so entries the tar builder refuses
+    // (`..`, links out of the archive) can be produced too.
+    fn archive(entries: &[(&str, EntryType, &str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, entry_type, link, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.as_gnu_mut().unwrap().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.as_gnu_mut().unwrap().linkname[..link.len()].copy_from_slice(link.as_bytes());
+            header.set_entry_type(*entry_type);
+            header.set_mode(0o644);
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn refuses_to_write_below_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        let destination = dir.path().join("download");
+
+        let data = archive(&[
+            ("app/", EntryType::Directory, "", b""),
+            ("app/link", EntryType::Symlink, outside.to_str().unwrap(), b""),
+            ("app/link/evil", EntryType::Regular, "", b"pwned"),
+        ]);
+
+        let error = extract_download(data.as_slice(), "/app", &destination).unwrap_err();
+        assert!(error.contains("below symlink"), "{}", error);
+        assert!(!outside.join("evil").exists());
+    }
+
+    #[test]
+    fn refuses_hardlinks_outside_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("download");
+
+        let data = archive(&[
+            ("app/", EntryType::Directory, "", b""),
+            ("app/passwd", EntryType::Link, "etc/passwd", b""),
+        ]);
+
+        let error = extract_download(data.as_slice(), "/app", &destination).unwrap_err();
+        assert!(error.contains("points outside"), "{}", error);
+        assert!(!destination.join("passwd").exists());
+    }
+
+    #[test]
+    fn links_hardlinks_inside_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("download");
+
+        let data = archive(&[
+            ("app/", EntryType::Directory, "", b""),
+            ("app/a", EntryType::Regular, "", b"data"),
+            ("app/b", EntryType::Link, "app/a", b""),
+        ]);
+
+        let (written, files, bytes) = extract_download(data.as_slice(), "/app", &destination).unwrap();
+        assert_eq!((written, files, bytes), (destination.clone(), 2, 4));
+        assert_eq!(std::fs::read(destination.join("b")).unwrap(), b"data");
+    }
+
+    #[test]
+    fn skips_parent_dir_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let destination = dir.path().join("download");
+
+        let data = archive(&[
+            ("app/", EntryType::Directory, "", b""),
+            ("app/../escape", EntryType::Regular, "", b"pwned"),
+            ("app/ok", EntryType::Regular, "", b"fine"),
+        ]);
+
+        let (_, files, _) = extract_download(data.as_slice(), "/app", &destination).unwrap();
+        assert_eq!(files, 1);
+        assert!(!dir.path().join("escape").exists());
+        assert!(destination.join("ok").exists());
+    }
+
+    #[test]
+    fn downloads_into_existing_directories_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let file = archive(&[("hosts", EntryType::Regular, "", b"127.0.0.1")]);
+        let (written, files, bytes) = extract_download(file.as_slice(), "/etc/hosts", dir.path()).unwrap();
+        assert_eq!((written, files, bytes), (dir.path().join("hosts"), 1, 9));
+        assert_eq!(std::fs::read(dir.path().join("hosts")).unwrap(), b"127.0.0.1");
+
+        let tree = archive(&[
+            ("conf/", EntryType::Directory, "", b""),
+            ("conf/app.ini", EntryType::Regular, "", b"x=1"),
+        ]);
+        let (written, _, _) = extract_download(tree.as_slice(), "/etc/conf", dir.path()).unwrap();
+        assert_eq!(written, dir.path().join("conf"));
+        assert!(dir.path().join("conf/app.ini").exists());
+
+        let fresh = dir.path().join("fresh");
+        let (written, _, _) = extract_download(tree.as_slice(), "/etc/conf", &fresh).unwrap();
+        assert_eq!(written, fresh);
+        assert!(fresh.join("app.ini").exists());
+    }
+
+    #[test]
+    fn keeps_text_cut_inside_a_character_at_the_limit() {
+        let data = archive(&[("notes.txt", EntryType::Regular, "", "ab€".as_bytes())]);
+
+        let content = read_content(data.as_slice(), "/notes.txt", 3).unwrap();
+        assert_eq!(content.content.as_deref(), Some("ab"));
+        assert!(content.truncated);
+        assert!(!content.binary);
+
+        let data = archive(&[("blob", EntryType::Regular, "", b"\xffabc")]);
+        let content = read_content(data.as_slice(), "/blob", 16).unwrap();
+        assert!(content.binary);
+        assert_eq!(content.content, None);
+    }
+}
@@ -0,0 +1,118 @@
+use bollard::Docker;
+use flate2::read::GzDecoder;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct SavedManifest {
+    pub config: String,
+    pub layers: Vec<String>,
+}
+
+// A `docker save` tarball spooled to a temporary file. Entries are located by their
+// offset in the file so layers can be read in manifest order without unpacking them.
+pub struct ImageArchive {
+    file: NamedTempFile,
+    entries: HashMap<String, (u64, u64)>,
+    pub manifest: SavedManifest,
+}
+
+impl ImageArchive {
+    pub async fn export(docker: &Docker, image: &str) -> Result<Self, String> {
+        let file = NamedTempFile::new()
+            .map_err(|e| format!("Failed to create temporary file: {}", e))?;
+        let output = file.reopen()
+            .map_err(|e| format!("Failed to open temporary file: {}", e))?;
+        let mut output = tokio::fs::File::from_std(output);
+
+        let mut stream = docker.export_image(image);
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Failed to export image: {}", e))?;
+            output.write_all(&chunk).await
+                .map_err(|e| format!("Failed to write image archive: {}", e))?;
+        }
+        output.flush().await
+            .map_err(|e| format!("Failed to write image archive: {}", e))?;
+
+        tauri::async_runtime::spawn_blocking(move || Self::index(file))
+            .await
+            .map_err(|e| format!("Failed to read image archive: {}", e))?
+    }
+
+    fn index(file: NamedTempFile) -> Result<Self, String> {
+        let reader = BufReader::new(file.reopen()
+            .map_err(|e| format!("Failed to open image archive: {}", e))?);
+        let mut archive = tar::Archive::new(reader);
+
+        let mut entries = HashMap::new();
+        for entry in archive.entries().map_err(|e| format!("Failed to read image archive: {}", e))? {
+            let entry = entry.map_err(|e| format!("Failed to read image archive: {}", e))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()
+                .map_err(|e| format!("Failed to read image archive: {}", e))?
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .to_string();
+            entries.insert(path, (entry.raw_file_position(), entry.size()));
+        }
+
+        let mut archive = Self {
+            file,
+            entries,
+            manifest: SavedManifest::default(),
+        };
+
+        let manifests: Vec<SavedManifest> = serde_json::from_reader(archive.open_entry("manifest.json")?)
+            .map_err(|e| format!("Invalid image manifest: {}", e))?;
+        archive.manifest = manifests.into_iter().next()
+            .ok_or_else(|| "Image archive contains no image".to_string())?;
+
+        Ok(archive)
+    }
+
+    pub fn open_entry(&self, name: &str) -> Result<impl Read, String> {
+        let &(offset, size) = self.entries.get(name)
+            .ok_or_else(|| format!("Image archive has no entry {}", name))?;
+
+        let mut file = self.file.reopen()
+            .map_err(|e| format!("Failed to open image archive: {}", e))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| format!("Failed to read image archive: {}", e))?;
+
+        Ok(BufReader::new(file.take(size)))
+    }
+
+    pub fn config(&self) -> Result<serde_json::Value, String> {
+        serde_json::from_reader(self.open_entry(&self.manifest.config)?)
+            .map_err(|e| format!("Invalid image config: {}", e))
+    }
+
+    // Layers are plain tars in classic `docker save` output but may be gzipped blobs
+    // in the OCI layout newer engines write.
+    pub fn open_layer(&self, index: usize) -> Result<tar::Archive<Box<dyn Read>>, String> {
+        let name = self.manifest.layers.get(index)
+            .ok_or_else(|| format!("Image has no layer {}", index))?;
+
+        let mut reader = self.open_entry(name)?;
+        let mut magic = [0u8; 2];
+        let read = reader.read(&mut magic)
+            .map_err(|e| format!("Failed to read layer {}: {}", name, e))?;
+        let gzipped = magic[..read] == [0x1f, 0x8b];
+        let reader = Cursor::new(magic[..read].to_vec()).chain(reader);
+
+        let reader: Box<dyn Read> = if gzipped {
+            Box::new(GzDecoder::new(reader))
+        } else {
+            Box::new(reader)
+        };
+
+        Ok(tar::Archive::new(reader))
+    }
+}
@@ -0,0 +1,385 @@
+use bollard::auth::DockerCredentials;
+use bollard::query_parameters::{BuildImageOptions, TagImageOptions};
+use futures_util::StreamExt;
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use tempfile::NamedTempFile;
+
+use crate::credentials::{credentials_for_registry, registry_host};
+use crate::image_transfer::counted_file_stream;
+use crate::images::{split_digest, split_tag};
+use crate::operations::Operations;
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BuildOutputEvent {
+    pub operation_id: String,
+    pub stream: Option<String>,
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+struct IgnorePattern {
+    matcher: GlobMatcher,
+    exception: bool,
+}
+
+// .dockerignore semantics: patterns are relative to the context root, a pattern
+// that matches a directory excludes everything below it, `!` re-includes and the
+// last matching pattern wins.
+struct DockerIgnore {
+    patterns: Vec<IgnorePattern>,
+}
+
+impl DockerIgnore {
+    fn load(context: &Path) -> Result<Self, String> {
+        let content = match std::fs::read_to_string(context.join(".dockerignore")) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read .dockerignore: {}", e)),
+        };
+
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (exception, pattern) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, line),
+            };
+
+            let pattern = pattern.trim_start_matches("./").trim_start_matches('/').trim_end_matches('/');
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let glob = globset::GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .or_else(|_| Glob::new(&globset::escape(pattern)))
+                .map_err(|e| format!("Invalid .dockerignore pattern {}: {}", line, e))?;
+
+            patterns.push(IgnorePattern {
+                matcher: glob.compile_matcher(),
+                exception,
+            });
+        }
+
+        Ok(Self { patterns })
+    }
+
+    fn has_exceptions(&self) -> bool {
+        self.patterns.iter().any(|p| p.exception)
+    }
+
+    fn is_ignored(&self, relative: &str) -> bool {
+        let mut ignored = false;
+
+        for pattern in &self.patterns {
+            let matched = pattern.matcher.is_match(relative)
+                || relative.match_indices('/').any(|(i, _)| pattern.matcher.is_match(&relative[..i]));
+
+            if matched {
+                ignored = !pattern.exception;
+            }
+        }
+
+        ignored
+    }
+}
+
+fn tar_name(relative: &Path) -> String {
+    relative.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn append_dir_contents(
+    builder: &mut tar::Builder<std::fs::File>,
+    context: &Path,
+    dir: &Path,
+    ignore: &DockerIgnore,
+    always_include: &[&str],
+) -> Result<(), String> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let relative = path.strip_prefix(context).unwrap_or(&path);
+        let name = tar_name(relative);
+        let file_type = std::fs::symlink_metadata(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .file_type();
+
+        let ignored = ignore.is_ignored(&name) && !always_include.contains(&name.as_str());
+
+        if file_type.is_dir() {
+            // An ignored directory may still contain re-included files or the Dockerfile.
+            let prefix = format!("{}/", name);
+            let needed = always_include.iter().any(|p| p.starts_with(&prefix));
+            if ignored && !ignore.has_exceptions() && !needed {
+                continue;
+            }
+            if !ignored {
+                builder.append_dir(&name, &path)
+                    .map_err(|e| format!("Failed to add {} to build context: {}", name, e))?;
+            }
+            append_dir_contents(builder, context, &path, ignore, always_include)?;
+        } else if !ignored {
+            builder.append_path_with_name(&path, &name)
+                .map_err(|e| format!("Failed to add {} to build context: {}", name, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Spooled to a temp file, a context with node_modules or datasets easily outgrows memory.
+fn build_context_tar(context: &Path, dockerfile: &str) -> Result<NamedTempFile, String> {
+    let ignore = DockerIgnore::load(context)?;
+
+    let file = NamedTempFile::new()
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut builder = tar::Builder::new(file.reopen()
+        .map_err(|e| format!("Failed to open temp file: {}", e))?);
+    builder.follow_symlinks(false);
+
+    // The daemon needs these even when .dockerignore lists them, like the docker CLI does.
+    let dockerfile = dockerfile.replace('\\', "/");
+    let always_include = [dockerfile.trim_start_matches("./"), ".dockerignore"];
+    append_dir_contents(&mut builder, context, context, &ignore, &always_include)?;
+
+    builder.into_inner()
+        .map_err(|e| format!("Failed to finish build context: {}", e))?;
+
+    Ok(file)
+}
+
+// Credentials for the registries of the FROM images, sent as X-Registry-Config.
+async fn base_image_credentials(dockerfile: &str) -> HashMap<String, DockerCredentials> {
+    let mut stages: Vec<String> = Vec::new();
+    let mut credentials = HashMap::new();
+
+    for line in dockerfile.lines() {
+        let mut words = line.split_whitespace();
+        if !words.next().is_some_and(|w| w.eq_ignore_ascii_case("from")) {
+            continue;
+        }
+
+        let args: Vec<&str> = words.filter(|w| !w.starts_with("--")).collect();
+        let Some(image) = args.first() else {
+            continue;
+        };
+
+        if let [_, as_kw, stage] = args.as_slice() {
+            if as_kw.eq_ignore_ascii_case("as") {
+                stages.push(stage.to_lowercase());
+            }
+        }
+
+        if image.contains('$') || *image == "scratch" || stages.contains(&image.to_lowercase()) {
+            continue;
+        }
+
+        let registry = registry_host(image);
+        if let Some(found) = credentials_for_registry(&registry).await {
+            if let Some(server) = found.serveraddress.clone() {
+                credentials.insert(server, found);
+            }
+        }
+    }
+
+    credentials
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn build_image(
+    context_dir: String,
+    dockerfile: Option<String>,
+    tags: Option<Vec<String>>,
+    build_args: Option<HashMap<String, String>>,
+    target: Option<String>,
+    no_cache: Option<bool>,
+    platform: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<String, String> {
+    let docker = get_docker_connection(state)?;
+
+    let context = PathBuf::from(&context_dir);
+    let dockerfile = dockerfile.unwrap_or_else(|| "Dockerfile".to_string());
+    let tags = tags.unwrap_or_default();
+
+    if let Some(tag) = tags.iter().find(|tag| split_digest(tag).1.is_some()) {
+        return Err(format!("Invalid tag {}: an image cannot be tagged with a digest", tag));
+    }
+
+    let dockerfile_content = std::fs::read_to_string(context.join(&dockerfile))
+        .map_err(|e| format!("Failed to read {}: {}", dockerfile, e))?;
+
+    let tar_context = context.clone();
+    let tar_dockerfile = dockerfile.clone();
+    let context_tar = tauri::async_runtime::spawn_blocking(move || build_context_tar(&tar_context, &tar_dockerfile))
+        .await
+        .map_err(|e| format!("Failed to pack build context: {}", e))??;
+    let (context_body, _, _) = counted_file_stream(&context_tar.path().to_string_lossy()).await?;
+
+    let options = BuildImageOptions {
+        dockerfile: dockerfile.replace('\\', "/"),
+        t: tags.first().cloned(),
+        buildargs: build_args,
+        target: target.unwrap_or_default(),
+        nocache: no_cache.unwrap_or(false),
+        platform: platform.unwrap_or_default(),
+        rm: true,
+        ..Default::default()
+    };
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let credentials = base_image_credentials(&dockerfile_content).await;
+    let mut build_stream = docker.build_image(
+        options,
+        Some(credentials),
+        Some(bollard::body_try_stream(context_body)),
+    );
+
+    let mut image_id: Option<String> = None;
+
+    loop {
+        let item = tokio::select! {
+            item = build_stream.next() => item,
+            _ = &mut cancel_future => return Err("Build cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| format!("Build failed: {}", e))?;
+
+        if let Some(id) = info.aux.as_ref().and_then(|aux| aux.id.clone()) {
+            image_id = Some(id);
+        }
+
+        let error = info.error_detail.as_ref()
+            .and_then(|d| d.message.clone())
+            .or(info.error.clone());
+
+        let _ = window.emit("image-build-output", BuildOutputEvent {
+            operation_id: operation.id.clone(),
+            stream: info.stream,
+            status: info.status,
+            error: error.clone(),
+        });
+
+        if let Some(error) = error {
+            return Err(format!("Build failed: {}", error));
+        }
+    }
+
+    let image_id = image_id.ok_or_else(|| "Build finished without producing an image".to_string())?;
+
+    for tag in tags.iter().skip(1) {
+        let (repo, tag) = split_tag(tag);
+        let options = TagImageOptions {
+            repo: Some(repo),
+            tag,
+        };
+
+        docker.tag_image(&image_id, Some(options))
+            .await
+            .map_err(|e| format!("Failed to tag image: {}", e))?;
+    }
+
+    Ok(image_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> DockerIgnore {
+        DockerIgnore::parse(content).unwrap()
+    }
+
+    #[test]
+    fn matches_the_documented_examples() {
+        let ignore = parse("# comment\n*/temp*\n*/*/temp*\ntemp?\n");
+
+        assert!(ignore.is_ignored("somedir/temporary.txt"));
+        assert!(ignore.is_ignored("somedir/temp"));
+        assert!(ignore.is_ignored("somedir/subdir/temporary.txt"));
+        assert!(ignore.is_ignored("tempa"));
+        assert!(!ignore.is_ignored("temporary.txt"));
+        assert!(!ignore.is_ignored("tempab"));
+        assert!(!ignore.is_ignored("comment"));
+    }
+
+    #[test]
+    fn double_star_matches_any_depth() {
+        let ignore = parse("**/*.go\n");
+
+        assert!(ignore.is_ignored("main.go"));
+        assert!(ignore.is_ignored("cmd/server/main.go"));
+        assert!(!ignore.is_ignored("main.rs"));
+    }
+
+    #[test]
+    fn directory_pattern_excludes_its_contents() {
+        let ignore = parse("node_modules\n/build/\n./dist\n");
+
+        assert!(ignore.is_ignored("node_modules"));
+        assert!(ignore.is_ignored("node_modules/left-pad/index.js"));
+        assert!(ignore.is_ignored("build/out/app"));
+        assert!(ignore.is_ignored("dist/app.js"));
+        assert!(!ignore.is_ignored("src/node_modules.rs"));
+    }
+
+    #[test]
+    fn exception_reincludes_a_file() {
+        let ignore = parse("*.md\n!README.md\n");
+
+        assert!(ignore.is_ignored("CHANGELOG.md"));
+        assert!(!ignore.is_ignored("README.md"));
+        assert!(ignore.has_exceptions());
+    }
+
+    #[test]
+    fn last_matching_pattern_wins() {
+        let ignore = parse("*.md\n!README*.md\nREADME-secret.md\n");
+        assert!(!ignore.is_ignored("README.md"));
+        assert!(ignore.is_ignored("README-secret.md"));
+
+        let ignore = parse("*.md\nREADME-secret.md\n!README*.md\n");
+        assert!(!ignore.is_ignored("README.md"));
+        assert!(!ignore.is_ignored("README-secret.md"));
+    }
+
+    #[test]
+    fn exception_inside_ignored_directory() {
+        let ignore = parse("docs\n!docs/keep.txt\n");
+
+        assert!(ignore.is_ignored("docs/drop.txt"));
+        assert!(!ignore.is_ignored("docs/keep.txt"));
+    }
+}
@@ -0,0 +1,521 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use tar::EntryType;
+
+use crate::image_archive::ImageArchive;
+use crate::{get_docker_connection, DockerConnection};
+
+const WHITEOUT_PREFIX: &str = ".wh.";
+const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+const WASTED_FILES_LIMIT: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerFileNode {
+    pub name: String,
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub change: String,
+    pub link_target: Option<String>,
+    pub children: Vec<LayerFileNode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerContents {
+    pub index: usize,
+    pub digest: String,
+    pub created_by: Option<String>,
+    pub size: u64,
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub wasted_size: u64,
+    pub tree: Vec<LayerFileNode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WastedFile {
+    pub path: String,
+    pub occurrences: usize,
+    pub wasted_size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageLayerContents {
+    pub image_id: String,
+    pub total_size: u64,
+    pub wasted_size: u64,
+    pub efficiency: f64,
+    pub layers: Vec<LayerContents>,
+    pub wasted_files: Vec<WastedFile>,
+}
+
+struct LayerEntry {
+    path: String,
+    kind: &'static str,
+    size: u64,
+    link_target: Option<String>,
+}
+
+struct FileChange {
+    kind: &'static str,
+    size: u64,
+    change: &'static str,
+    link_target: Option<String>,
+}
+
+#[derive(Default)]
+struct TreeBuilder {
+    change: Option<FileChange>,
+    children: BTreeMap<String, TreeBuilder>,
+}
+
+impl TreeBuilder {
+    fn insert(&mut self, path: &str, change: FileChange) {
+        let mut node = self;
+        for part in path.split('/') {
+            node = node.children.entry(part.to_string()).or_default();
+        }
+        node.change = Some(change);
+    }
+
+    fn build(children: BTreeMap<String, TreeBuilder>, parent: &str) -> Vec<LayerFileNode> {
+        children.into_iter()
+            .map(|(name, node)| {
+                let path = if parent.is_empty() { name.clone() } else { format!("{}/{}", parent, name) };
+                let children = Self::build(node.children, &path);
+                let child_size: u64 = children.iter().map(|c| c.size).sum();
+
+                match node.change {
+                    Some(change) => LayerFileNode {
+                        name,
+                        path,
+                        kind: change.kind.to_string(),
+                        size: change.size.max(child_size),
+                        change: change.change.to_string(),
+                        link_target: change.link_target,
+                        children,
+                    },
+                    // Parent directories that only exist in lower layers.
+                    None => LayerFileNode {
+                        name,
+                        path,
+                        kind: "dir".to_string(),
+                        size: child_size,
+                        change: "unchanged".to_string(),
+                        link_target: None,
+                        children,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+fn entry_kind(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::Directory => "dir",
+        EntryType::Symlink => "symlink",
+        EntryType::Link => "hardlink",
+        t if t.is_file() => "file",
+        _ => "other",
+    }
+}
+
+fn read_layer_entries<R: Read>(mut layer: tar::Archive<R>, index: usize) -> Result<Vec<LayerEntry>, String> {
+    let mut entries = Vec::new();
+
+    for entry in layer.entries().map_err(|e| format!("Failed to read layer {}: {}", index, e))? {
+        let entry = entry.map_err(|e| format!("Failed to read layer {}: {}", index, e))?;
+
+        let path = entry.path()
+            .map_err(|e| format!("Failed to read layer {}: {}", index, e))?
+            .to_string_lossy()
+            .trim_start_matches("./")
+            .trim_matches('/')
+            .to_string();
+        if path.is_empty() {
+            continue;
+        }
+
+        let link_target = entry.link_name().ok().flatten()
+            .map(|target| target.to_string_lossy().to_string());
+
+        entries.push(LayerEntry {
+            path,
+            kind: entry_kind(entry.header().entry_type()),
+            size: entry.size(),
+            link_target,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn split_parent(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+fn under(dir: &str) -> String {
+    if dir.is_empty() { String::new() } else { format!("{}/", dir) }
+}
+
+fn remove_below(current: &mut BTreeMap<String, (&'static str, u64)>, prefix: &str) -> Vec<(String, (&'static str, u64))> {
+    let paths: Vec<String> = current.range(prefix.to_string()..)
+        .take_while(|(path, _)| path.starts_with(prefix))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    paths.into_iter()
+        .filter_map(|path| current.remove(&path).map(|entry| (path, entry)))
+        .collect()
+}
+
+struct LayerChanges {
+    size: u64,
+    added: usize,
+    modified: usize,
+    deleted: usize,
+    wasted_size: u64,
+    tree: Vec<LayerFileNode>,
+}
+
+// The merged filesystem of the layers applied so far, so every write or whiteout can be
+// classified and the bytes it hides in lower layers counted as wasted.
+#[derive(Default)]
+struct LayerMerge {
+    current: BTreeMap<String, (&'static str, u64)>,
+    writes: HashMap<String, usize>,
+    wasted_by_path: HashMap<String, u64>,
+    total_size: u64,
+}
+
+impl LayerMerge {
+    fn apply(&mut self, entries: Vec<LayerEntry>) -> LayerChanges {
+        let mut tree = TreeBuilder::default();
+        let (mut added, mut modified, mut deleted) = (0, 0, 0);
+        let mut layer_size = 0u64;
+        let mut layer_wasted = 0u64;
+
+        // Paths this layer deletes, a file the layer then writes again replaces them.
+        let mut whited_out: HashSet<String> = HashSet::new();
+
+        let wasted_by_path = &mut self.wasted_by_path;
+        let mut discard = |path: String, size: u64| {
+            if size > 0 {
+                *wasted_by_path.entry(path).or_default() += size;
+                layer_wasted += size;
+            }
+        };
+
+        // Whiteouts only hide lower layers, so apply them before this layer's own files.
+        for entry in &entries {
+            let (dir, name) = split_parent(&entry.path);
+
+            if name == OPAQUE_WHITEOUT {
+                let prefix = under(dir);
+
+                // Report each hidden top level child once, with everything below it.
+                let mut per_child: BTreeMap<String, (&'static str, u64)> = BTreeMap::new();
+                for (path, (kind, size)) in remove_below(&mut self.current, &prefix) {
+                    let child = path[prefix.len()..].split('/').next().unwrap_or_default().to_string();
+                    let is_child = path.len() == prefix.len() + child.len();
+                    let reported = per_child.entry(child).or_insert(("dir", 0));
+                    if is_child {
+                        reported.0 = kind;
+                    }
+                    reported.1 += size;
+                    discard(path, size);
+                }
+
+                for (child, (kind, size)) in per_child {
+                    let path = format!("{}{}", prefix, child);
+                    deleted += 1;
+                    whited_out.insert(path.clone());
+                    tree.insert(&path, FileChange {
+                        kind,
+                        size,
+                        change: "deleted",
+                        link_target: None,
+                    });
+                }
+            } else if let Some(target) = name.strip_prefix(WHITEOUT_PREFIX) {
+                let target = format!("{}{}", under(dir), target);
+
+                let mut hidden = remove_below(&mut self.current, &format!("{}/", target));
+                let kind = match self.current.remove(&target) {
+                    Some((kind, size)) => {
+                        hidden.push((target.clone(), (kind, size)));
+                        kind
+                    },
+                    None => "other",
+                };
+
+                let mut removed = 0u64;
+                for (path, (_, size)) in hidden {
+                    removed += size;
+                    discard(path, size);
+                }
+
+                deleted += 1;
+                whited_out.insert(target.clone());
+                tree.insert(&target, FileChange {
+                    kind,
+                    size: removed,
+                    change: "deleted",
+                    link_target: None,
+                });
+            }
+        }
+
+        for entry in entries {
+            let (_, name) = split_parent(&entry.path);
+            if name.starts_with(WHITEOUT_PREFIX) {
+                continue;
+            }
+
+            layer_size += entry.size;
+            *self.writes.entry(entry.path.clone()).or_default() += 1;
+
+            let change = match self.current.insert(entry.path.clone(), (entry.kind, entry.size)) {
+                // Directories are repeated in every layer that touches their contents.
+                Some(_) if entry.kind == "dir" => "unchanged",
+                Some((_, previous)) => {
+                    modified += 1;
+                    discard(entry.path.clone(), previous);
+                    "modified"
+                },
+                // Deleted and written again in the same layer, the old bytes were discarded above.
+                None if whited_out.remove(&entry.path) => {
+                    deleted -= 1;
+                    modified += 1;
+                    "modified"
+                },
+                None => {
+                    added += 1;
+                    "added"
+                },
+            };
+
+            tree.insert(&entry.path, FileChange {
+                kind: entry.kind,
+                size: entry.size,
+                change,
+                link_target: entry.link_target,
+            });
+        }
+
+        self.total_size += layer_size;
+
+        LayerChanges {
+            size: layer_size,
+            added,
+            modified,
+            deleted,
+            wasted_size: layer_wasted,
+            tree: TreeBuilder::build(tree.children, ""),
+        }
+    }
+
+    // Total wasted bytes and the paths wasting the most.
+    fn wasted_files(&self) -> (u64, Vec<WastedFile>) {
+        let wasted_size: u64 = self.wasted_by_path.values().sum();
+
+        let mut wasted_files: Vec<WastedFile> = self.wasted_by_path.iter()
+            .map(|(path, &wasted_size)| WastedFile {
+                occurrences: self.writes.get(path).copied().unwrap_or(1),
+                path: path.clone(),
+                wasted_size,
+            })
+            .collect();
+        wasted_files.sort_by(|a, b| b.wasted_size.cmp(&a.wasted_size).then_with(|| a.path.cmp(&b.path)));
+        wasted_files.truncate(WASTED_FILES_LIMIT);
+
+        (wasted_size, wasted_files)
+    }
+}
+
+fn analyze_layers(archive: &ImageArchive, image_id: String) -> Result<ImageLayerContents, String> {
+    let config = archive.config()?;
+
+    let diff_ids: Vec<String> = config["rootfs"]["diff_ids"].as_array()
+        .map(|ids| ids.iter().filter_map(|id| id.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+
+    let created_by: Vec<Option<String>> = config["history"].as_array()
+        .map(|history| history.iter()
+            .filter(|h| !h["empty_layer"].as_bool().unwrap_or(false))
+            .map(|h| h["created_by"].as_str().map(String::from))
+            .collect())
+        .unwrap_or_default();
+
+    let mut merge = LayerMerge::default();
+    let mut layers = Vec::new();
+
+    for index in 0..archive.manifest.layers.len() {
+        let entries = read_layer_entries(archive.open_layer(index)?, index)?;
+        let changes = merge.apply(entries);
+
+        layers.push(LayerContents {
+            index,
+            digest: diff_ids.get(index).cloned().unwrap_or_else(|| archive.manifest.layers[index].clone()),
+            created_by: created_by.get(index).cloned().flatten(),
+            size: changes.size,
+            added: changes.added,
+            modified: changes.modified,
+            deleted: changes.deleted,
+            wasted_size: changes.wasted_size,
+            tree: changes.tree,
+        });
+    }
+
+    let (wasted_size, wasted_files) = merge.wasted_files();
+    let total_size = merge.total_size;
+
+    let efficiency = if total_size == 0 {
+        1.0
+    } else {
+        1.0 - wasted_size as f64 / total_size as f64
+    };
+
+    Ok(ImageLayerContents {
+        image_id,
+        total_size,
+        wasted_size,
+        efficiency,
+        layers,
+        wasted_files,
+    })
+}
+
+#[tauri::command]
+pub async fn get_image_layer_contents(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<ImageLayerContents, String> {
+    let docker = get_docker_connection(state)?;
+
+    let archive = ImageArchive::export(&docker, &id).await?;
+
+    tauri::async_runtime::spawn_blocking(move || analyze_layers(&archive, id))
+        .await
+        .map_err(|e| format!("Failed to analyze image layers: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Item<'a> {
+        Dir(&'a str),
+        File(&'a str, usize),
+    }
+
+    fn layer(items: &[Item]) -> Vec<LayerEntry> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for item in items {
+            let mut header = tar::Header::new_gnu();
+            header.set_mode(0o755);
+            match item {
+                Item::Dir(path) => {
+                    header.set_entry_type(EntryType::Directory);
+                    header.set_size(0);
+                    builder.append_data(&mut header, path, std::io::empty()).unwrap();
+                },
+                Item::File(path, size) => {
+                    header.set_entry_type(EntryType::Regular);
+                    header.set_size(*size as u64);
+                    builder.append_data(&mut header, path, vec![0u8; *size].as_slice()).unwrap();
+                },
+            }
+        }
+        let data = builder.into_inner().unwrap();
+
+        read_layer_entries(tar::Archive::new(data.as_slice()), 0).unwrap()
+    }
+
+    fn find<'a>(nodes: &'a [LayerFileNode], path: &str) -> Option<&'a LayerFileNode> {
+        nodes.iter().find_map(|node| {
+            if node.path == path {
+                Some(node)
+            } else {
+                find(&node.children, path)
+            }
+        })
+    }
+
+    #[test]
+    fn whiteout_deletes_lower_file() {
+        let mut merge = LayerMerge::default();
+        merge.apply(layer(&[Item::Dir("etc/"), Item::File("etc/app.conf", 40)]));
+
+        let changes = merge.apply(layer(&[Item::Dir("etc/"), Item::File("etc/.wh.app.conf", 0)]));
+        assert_eq!((changes.added, changes.modified, changes.deleted), (0, 0, 1));
+        assert_eq!(changes.wasted_size, 40);
+        let node = find(&changes.tree, "etc/app.conf").unwrap();
+        assert_eq!((node.change.as_str(), node.kind.as_str(), node.size), ("deleted", "file", 40));
+        assert!(find(&changes.tree, "etc/.wh.app.conf").is_none());
+
+        let (wasted_size, wasted_files) = merge.wasted_files();
+        assert_eq!(wasted_size, 40);
+        assert_eq!(wasted_files[0].path, "etc/app.conf");
+    }
+
+    #[test]
+    fn opaque_whiteout_hides_directory_contents() {
+        let mut merge = LayerMerge::default();
+        merge.apply(layer(&[
+            Item::Dir("cache/"),
+            Item::File("cache/a", 10),
+            Item::Dir("cache/sub/"),
+            Item::File("cache/sub/b", 20),
+        ]));
+
+        let changes = merge.apply(layer(&[
+            Item::Dir("cache/"),
+            Item::File("cache/.wh..wh..opq", 0),
+            Item::File("cache/c", 5),
+        ]));
+        assert_eq!((changes.added, changes.modified, changes.deleted), (1, 0, 2));
+        assert_eq!(changes.wasted_size, 30);
+        assert_eq!(find(&changes.tree, "cache/a").unwrap().change, "deleted");
+        let sub = find(&changes.tree, "cache/sub").unwrap();
+        assert_eq!((sub.change.as_str(), sub.kind.as_str(), sub.size), ("deleted", "dir", 20));
+        assert_eq!(find(&changes.tree, "cache/c").unwrap().change, "added");
+        assert_eq!(find(&changes.tree, "cache").unwrap().change, "unchanged");
+    }
+
+    #[test]
+    fn whiteout_and_readd_in_same_layer_is_a_modification() {
+        let mut merge = LayerMerge::default();
+        merge.apply(layer(&[Item::File("app.bin", 100)]));
+
+        let changes = merge.apply(layer(&[Item::File(".wh.app.bin", 0), Item::File("app.bin", 60)]));
+        assert_eq!((changes.added, changes.modified, changes.deleted), (0, 1, 0));
+        assert_eq!(changes.wasted_size, 100);
+        let node = find(&changes.tree, "app.bin").unwrap();
+        assert_eq!((node.change.as_str(), node.size), ("modified", 60));
+
+        let (wasted_size, wasted_files) = merge.wasted_files();
+        assert_eq!(wasted_size, 100);
+        assert_eq!(wasted_files[0].occurrences, 2);
+    }
+
+    #[test]
+    fn opaque_whiteout_and_readd_in_same_layer_is_a_modification() {
+        let mut merge = LayerMerge::default();
+        merge.apply(layer(&[Item::Dir("data/"), Item::File("data/x", 10), Item::File("data/y", 20)]));
+
+        let changes = merge.apply(layer(&[
+            Item::Dir("data/"),
+            Item::File("data/.wh..wh..opq", 0),
+            Item::File("data/x", 15),
+        ]));
+        assert_eq!((changes.added, changes.modified, changes.deleted), (0, 1, 1));
+        assert_eq!(changes.wasted_size, 30);
+        assert_eq!(find(&changes.tree, "data/x").unwrap().change, "modified");
+        assert_eq!(find(&changes.tree, "data/y").unwrap().change, "deleted");
+        assert_eq!(merge.total_size, 45);
+    }
+}
@@ -0,0 +1,436 @@
+use bollard::query_parameters::{CreateImageOptions, ImportImageOptions};
+use bollard::Docker;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::Emitter;
+use tokio::io::AsyncWriteExt;
+use tokio_util::bytes::Bytes;
+use tokio_util::io::ReaderStream;
+
+use crate::images::{EmitThrottle, PROGRESS_EMIT_INTERVAL};
+use crate::operations::Operations;
+use crate::{connection_address, docker_for_host, get_docker_connection, host_address, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArchiveProgressEvent {
+    pub operation_id: String,
+    pub path: String,
+    pub status: String,
+    pub current: u64,
+    pub total: u64,
+    pub percent: f64,
+}
+
+impl ArchiveProgressEvent {
+    pub fn new(operation_id: &str, path: &str, status: &str, current: u64, total: u64) -> Self {
+        let percent = if total > 0 {
+            (current as f64 / total as f64 * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            operation_id: operation_id.to_string(),
+            path: path.to_string(),
+            status: status.to_string(),
+            current,
+            total,
+            percent,
+        }
+    }
+
+    // Save and backup totals are estimates, so never claim completion early.
+    pub fn estimated(mut self) -> Self {
+        self.percent = self.percent.min(99.0);
+        self
+    }
+
+    pub fn complete(mut self) -> Self {
+        self.percent = 100.0;
+        self
+    }
+}
+
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+// `Loaded image: nginx:latest` or `Loaded image ID: sha256:...` for untagged images.
+fn loaded_image(stream: &str) -> Option<String> {
+    let line = stream.trim();
+    line.strip_prefix("Loaded image ID: ")
+        .or_else(|| line.strip_prefix("Loaded image: "))
+        .map(String::from)
+}
+
+// Streams a file into a request body, counting the bytes the daemon has consumed.
+pub async fn counted_file_stream(
+    path: &str
+) -> Result<(impl Stream<Item = std::io::Result<Bytes>> + Send + 'static, u64, Arc<AtomicU64>), String> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let total = file.metadata()
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .len();
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let counter = sent.clone();
+    let stream = ReaderStream::new(file).inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        }
+    });
+
+    Ok((stream, total, sent))
+}
+
+#[tauri::command]
+pub async fn save_images(
+    ids: Vec<String>,
+    path: String,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<u64, String> {
+    let docker = get_docker_connection(state)?;
+
+    if ids.is_empty() {
+        return Err("No images selected".to_string());
+    }
+
+    // The tarball holds uncompressed layers, so the image sizes are a fair estimate.
+    let mut total = 0u64;
+    for id in &ids {
+        let image = docker.inspect_image(id)
+            .await
+            .map_err(|e| format!("Failed to inspect image {}: {}", id, e))?;
+        total += image.size.unwrap_or(0).max(0) as u64;
+    }
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    // Write next to the target and rename at the end, so a failed save never leaves
+    // a truncated tarball under the requested name.
+    let output = PathBuf::from(&path);
+    let partial = partial_path(&output);
+
+    let result: Result<u64, String> = async {
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+
+        let names: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let mut export_stream = docker.export_images(&names);
+        let mut throttle = EmitThrottle::default();
+        let mut written = 0u64;
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = export_stream.next() => chunk,
+                _ = &mut cancel_future => return Err("Save cancelled".to_string()),
+            };
+
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            let chunk = chunk.map_err(|e| format!("Failed to save images: {}", e))?;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+            written += chunk.len() as u64;
+
+            if throttle.ready(false) {
+                let _ = window.emit("image-save-progress", ArchiveProgressEvent::new(&operation.id, &path, "Saving", written, total).estimated());
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+
+        Ok(written)
+    }.await;
+
+    let written = match result {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        },
+    };
+
+    tokio::fs::rename(&partial, &output)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    window.emit("image-save-progress", ArchiveProgressEvent::new(&operation.id, &path, "Saved", written, written).complete())
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(written)
+}
+
+#[tauri::command]
+pub async fn load_images(
+    path: String,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<Vec<String>, String> {
+    let docker = get_docker_connection(state)?;
+
+    let (body, total, sent) = counted_file_stream(&path).await?;
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let mut load_stream = docker.import_image(ImportImageOptions::default(), bollard::body_try_stream(body), None);
+    let mut ticker = tokio::time::interval(PROGRESS_EMIT_INTERVAL);
+    let mut loaded = Vec::new();
+
+    loop {
+        let item = tokio::select! {
+            item = load_stream.next() => item,
+            _ = ticker.tick() => {
+                let current = sent.load(Ordering::Relaxed);
+                let _ = window.emit("image-load-progress", ArchiveProgressEvent::new(&operation.id, &path, "Loading", current, total));
+                continue;
+            },
+            _ = &mut cancel_future => return Err("Load cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| format!("Failed to load images: {}", e))?;
+
+        if let Some(error) = info.error_detail.and_then(|d| d.message).or(info.error) {
+            return Err(format!("Failed to load images: {}", error));
+        }
+
+        if let Some(image) = info.stream.as_deref().and_then(loaded_image) {
+            loaded.push(image);
+        }
+    }
+
+    window.emit("image-load-progress", ArchiveProgressEvent::new(&operation.id, &path, "Loaded", total, total).complete())
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(loaded)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_image(
+    path: String,
+    repo: Option<String>,
+    tag: Option<String>,
+    message: Option<String>,
+    changes: Option<Vec<String>>,
+    platform: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<String, String> {
+    let docker = get_docker_connection(state)?;
+
+    let (body, total, sent) = counted_file_stream(&path).await?;
+
+    let options = CreateImageOptions {
+        from_src: Some("-".to_string()),
+        repo,
+        tag,
+        message,
+        changes: changes.unwrap_or_default(),
+        platform: platform.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let mut import_stream = docker.create_image(Some(options), Some(bollard::body_try_stream(body)), None);
+    let mut ticker = tokio::time::interval(PROGRESS_EMIT_INTERVAL);
+    let mut image_id: Option<String> = None;
+
+    loop {
+        let item = tokio::select! {
+            item = import_stream.next() => item,
+            _ = ticker.tick() => {
+                let current = sent.load(Ordering::Relaxed);
+                let _ = window.emit("image-import-progress", ArchiveProgressEvent::new(&operation.id, &path, "Importing", current, total));
+                continue;
+            },
+            _ = &mut cancel_future => return Err("Import cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| format!("Failed to import image: {}", e))?;
+
+        if let Some(error) = info.error_detail.and_then(|d| d.message).or(info.error) {
+            return Err(format!("Failed to import image: {}", error));
+        }
+
+        // The daemon finishes with the new image id as the status.
+        if let Some(status) = info.status.filter(|s| s.starts_with("sha256:")) {
+            image_id = Some(status);
+        }
+    }
+
+    let image_id = image_id.ok_or_else(|| "Import finished without producing an image".to_string())?;
+
+    window.emit("image-import-progress", ArchiveProgressEvent::new(&operation.id, &path, "Imported", total, total).complete())
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(image_id)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransferProgressEvent {
+    pub operation_id: String,
+    pub reference: String,
+    pub from_host: String,
+    pub to_host: String,
+    pub status: String,
+    pub current: u64,
+    pub total: u64,
+    pub percent: f64,
+}
+
+fn host_client(host: Option<&str>, state: tauri::State<'_, DockerConnection>) -> Result<Docker, String> {
+    match host {
+        Some(host) => docker_for_host(host),
+        None => get_docker_connection(state),
+    }
+}
+
+// Pipes `docker save` on one daemon straight into `docker load` on another, so images
+// reach hosts without registry access and nothing is written to local disk.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer_image(
+    reference: String,
+    from_host: Option<String>,
+    to_host: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<Vec<String>, String> {
+    // `None`, `local` and the socket URL can all name the same daemon.
+    let address = |host: &Option<String>| match host {
+        Some(host) => host_address(host),
+        None => connection_address(&state),
+    };
+    if address(&from_host) == address(&to_host) {
+        return Err("Source and destination hosts are the same".to_string());
+    }
+
+    let from_label = from_host.clone().unwrap_or_else(|| "current".to_string());
+    let to_label = to_host.clone().unwrap_or_else(|| "current".to_string());
+
+    let source = host_client(from_host.as_deref(), state.clone())?;
+    let destination = host_client(to_host.as_deref(), state)?;
+
+    let total = source.inspect_image(&reference)
+        .await
+        .map_err(|e| format!("Failed to inspect image {} on {}: {}", reference, from_label, e))?
+        .size
+        .unwrap_or(0)
+        .max(0) as u64;
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let event = |status: &str, current: u64| {
+        let percent = if total > 0 { (current as f64 / total as f64 * 100.0).min(99.0) } else { 0.0 };
+        TransferProgressEvent {
+            operation_id: operation.id.clone(),
+            reference: reference.clone(),
+            from_host: from_label.clone(),
+            to_host: to_label.clone(),
+            status: status.to_string(),
+            current,
+            total,
+            percent,
+        }
+    };
+
+    // A failing save only shows up as a truncated tarball on the loading side, so keep
+    // the source error to report instead.
+    let sent = Arc::new(AtomicU64::new(0));
+    let source_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let counter = sent.clone();
+    let export_error = source_error.clone();
+    let body = source.export_image(&reference).map(move |chunk| match chunk {
+        Ok(chunk) => {
+            counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+            Ok(chunk)
+        },
+        Err(e) => {
+            *export_error.lock().unwrap() = Some(e.to_string());
+            Err(std::io::Error::other(e.to_string()))
+        },
+    });
+
+    let mut load_stream = destination.import_image(ImportImageOptions::default(), bollard::body_try_stream(body), None);
+    let mut ticker = tokio::time::interval(PROGRESS_EMIT_INTERVAL);
+    let mut loaded = Vec::new();
+
+    let failed = |e: String| match source_error.lock().unwrap().take() {
+        Some(source) => format!("Failed to save image on {}: {}", from_label, source),
+        None => format!("Failed to load image on {}: {}", to_label, e),
+    };
+
+    loop {
+        let item = tokio::select! {
+            item = load_stream.next() => item,
+            _ = ticker.tick() => {
+                let _ = window.emit("image-transfer-progress", event("Transferring", sent.load(Ordering::Relaxed)));
+                continue;
+            },
+            _ = &mut cancel_future => return Err("Transfer cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| failed(e.to_string()))?;
+
+        if let Some(error) = info.error_detail.and_then(|d| d.message).or(info.error) {
+            return Err(failed(error));
+        }
+
+        if let Some(image) = info.stream.as_deref().and_then(loaded_image) {
+            loaded.push(image);
+        }
+    }
+
+    if let Some(source) = source_error.lock().unwrap().take() {
+        return Err(format!("Failed to save image on {}: {}", from_label, source));
+    }
+
+    let mut done = event("Transferred", sent.load(Ordering::Relaxed));
+    done.percent = 100.0;
+    window.emit("image-transfer-progress", done)
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(loaded)
+}
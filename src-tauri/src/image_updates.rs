@@ -0,0 +1,174 @@
+use bollard::query_parameters::ListContainersOptionsBuilder;
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::credentials::registry_auth_for;
+use crate::images::normalize_reference;
+use crate::registry::{parse_reference, RegistryClient};
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateCheckContainer {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub image_id: String,
+    pub outdated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageUpdateStatus {
+    pub reference: String,
+    pub image_id: Option<String>,
+    pub local_digests: Vec<String>,
+    pub remote_digest: Option<String>,
+    // "up-to-date", "update-available", "unknown" or "error"
+    pub status: String,
+    pub error: Option<String>,
+    pub containers: Vec<UpdateCheckContainer>,
+}
+
+// Containers whose image tag was moved report the image id instead, there is nothing to look up.
+fn is_image_id(reference: &str) -> bool {
+    reference.starts_with("sha256:")
+        || (reference.len() >= 12 && reference.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn short_id(id: &str) -> String {
+    id.trim_start_matches("sha256:").chars().take(12).collect()
+}
+
+// RepoDigests of the image that belong to the same registry repository, e.g. `nginx@sha256:...`.
+fn matching_digests(repo_digests: &[String], registry: &str, repository: &str) -> Vec<String> {
+    repo_digests.iter()
+        .filter_map(|entry| {
+            let (entry_registry, entry_repository, digest) = parse_reference(entry);
+            (entry_registry == registry && entry_repository == repository).then_some(digest)
+        })
+        .collect()
+}
+
+async fn check_reference(
+    docker: &Docker,
+    clients: &mut HashMap<String, RegistryClient>,
+    reference: &str,
+    containers: Vec<(UpdateCheckContainer, String)>
+) -> ImageUpdateStatus {
+    let mut status = ImageUpdateStatus {
+        reference: reference.to_string(),
+        image_id: None,
+        local_digests: Vec::new(),
+        remote_digest: None,
+        status: "unknown".to_string(),
+        error: None,
+        containers: Vec::new(),
+    };
+
+    let local = docker.inspect_image(reference).await;
+    let (registry, repository, tag) = parse_reference(reference);
+
+    if let Ok(ref image) = local {
+        status.image_id = image.id.clone();
+        status.local_digests = matching_digests(
+            image.repo_digests.as_deref().unwrap_or_default(),
+            &registry,
+            &repository
+        );
+    }
+
+    let client = match clients.entry(registry.clone()) {
+        Entry::Occupied(entry) => Ok(entry.into_mut()),
+        Entry::Vacant(entry) => RegistryClient::new(&registry, registry_auth_for(&registry).await)
+            .map(|client| entry.insert(client)),
+    };
+
+    let remote = match client {
+        Ok(client) => client.manifest_digest(&repository, &tag).await,
+        Err(e) => Err(e),
+    };
+
+    match remote {
+        Ok(digest) => {
+            status.status = if status.local_digests.is_empty() {
+                // Built locally or loaded from an archive, never pulled from this registry.
+                "unknown".to_string()
+            } else if status.local_digests.contains(&digest) {
+                "up-to-date".to_string()
+            } else {
+                "update-available".to_string()
+            };
+            status.remote_digest = Some(digest);
+        },
+        Err(e) => {
+            status.status = "error".to_string();
+            status.error = Some(e);
+        },
+    }
+
+    let update_available = status.status == "update-available";
+    let current_id = status.image_id.clone().unwrap_or_default();
+
+    // A container is outdated when the registry has something newer, or when a newer
+    // image was already pulled but the container still runs the old one.
+    status.containers = containers.into_iter()
+        .map(|(mut container, image_id)| {
+            container.outdated = update_available || (!current_id.is_empty() && image_id != current_id);
+            container
+        })
+        .collect();
+
+    status
+}
+
+#[tauri::command]
+pub async fn check_image_updates(
+    all: Option<bool>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<Vec<ImageUpdateStatus>, String> {
+    let docker = get_docker_connection(state)?;
+
+    let options = ListContainersOptionsBuilder::default()
+        .all(all.unwrap_or(false))
+        .build();
+
+    let containers = docker.list_containers(Some(options)).await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    let mut by_reference: BTreeMap<String, Vec<(UpdateCheckContainer, String)>> = BTreeMap::new();
+    for container in containers {
+        let Some(image) = container.image else {
+            continue;
+        };
+        if is_image_id(&image) || image.contains('@') {
+            continue;
+        }
+
+        let id = container.id.unwrap_or_default();
+        let image_id = container.image_id.unwrap_or_default();
+
+        by_reference.entry(normalize_reference(&image)).or_default().push((
+            UpdateCheckContainer {
+                name: container.names.unwrap_or_default().first()
+                    .map(|n| n.trim_start_matches('/').to_string())
+                    .unwrap_or_else(|| id.clone()),
+                id: short_id(&id),
+                state: container.state.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+                image_id: short_id(&image_id),
+                outdated: false,
+            },
+            image_id,
+        ));
+    }
+
+    // One client per registry, credentials are resolved once and tokens stay cached.
+    let mut clients = HashMap::new();
+    let mut results = Vec::new();
+
+    for (reference, containers) in by_reference {
+        results.push(check_reference(&docker, &mut clients, &reference, containers).await);
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,875 @@
+use bollard::models::ProgressDetail;
+use bollard::query_parameters::{
+    CreateImageOptions,
+    ListContainersOptionsBuilder,
+    PruneImagesOptionsBuilder,
+    PushImageOptions,
+    RemoveImageOptions,
+    TagImageOptions,
+};
+use bollard::Docker;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::credentials::credentials_for;
+use crate::operations::Operations;
+use crate::{get_docker_connection, DockerConnection};
+
+pub const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerProgress {
+    pub id: String,
+    pub status: String,
+    pub current: i64,
+    pub total: i64,
+    pub progress: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageProgressEvent {
+    pub operation_id: String,
+    pub reference: String,
+    pub status: String,
+    pub layers: Vec<LayerProgress>,
+    pub current: i64,
+    pub total: i64,
+    pub percent: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageLayer {
+    pub id: String,
+    pub created: i64,
+    pub created_by: String,
+    pub size: i64,
+    pub tags: Vec<String>,
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageDetails {
+    pub id: String,
+    pub repo_tags: Vec<String>,
+    pub repo_digests: Vec<String>,
+    pub parent: String,
+    pub created: String,
+    pub author: String,
+    pub architecture: String,
+    pub variant: Option<String>,
+    pub os: String,
+    pub size: i64,
+    pub entrypoint: Vec<String>,
+    pub cmd: Vec<String>,
+    pub env: Vec<String>,
+    pub exposed_ports: Vec<String>,
+    pub volumes: Vec<String>,
+    pub labels: HashMap<String, String>,
+    pub user: String,
+    pub working_dir: String,
+    pub layers: Vec<ImageLayer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageContainerRef {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoveImageResult {
+    pub removed: bool,
+    pub containers: Vec<ImageContainerRef>,
+    pub untagged: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PruneImagesResult {
+    pub deleted: Vec<String>,
+    pub untagged: Vec<String>,
+    pub space_reclaimed: i64,
+}
+
+#[derive(Default)]
+pub struct EmitThrottle {
+    last: Option<Instant>,
+}
+
+impl EmitThrottle {
+    pub fn ready(&mut self, force: bool) -> bool {
+        let due = self.last.is_none_or(|at| at.elapsed() >= PROGRESS_EMIT_INTERVAL);
+        if force || due {
+            self.last = Some(Instant::now());
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// Folds the per-layer status messages of a pull stream into overall progress.
+#[derive(Default)]
+struct LayerProgressTracker {
+    status: String,
+    layers: Vec<LayerProgress>,
+    throttle: EmitThrottle,
+}
+
+impl LayerProgressTracker {
+    // Returns true when something other than a byte counter changed.
+    fn update(&mut self, id: Option<&str>, status: Option<&str>, detail: Option<&ProgressDetail>) -> bool {
+        let status = status.unwrap_or_default();
+
+        let Some(id) = id.filter(|_| is_layer_status(status)) else {
+            if !status.is_empty() {
+                self.status = status.to_string();
+            }
+            return true;
+        };
+
+        let index = match self.layers.iter().position(|l| l.id == id) {
+            Some(index) => index,
+            None => {
+                self.layers.push(LayerProgress {
+                    id: id.to_string(),
+                    status: String::new(),
+                    current: 0,
+                    total: 0,
+                    progress: 0.0,
+                });
+                self.layers.len() - 1
+            },
+        };
+        let layer = &mut self.layers[index];
+
+        let changed = layer.status != status;
+        layer.status = status.to_string();
+
+        if let Some(detail) = detail {
+            layer.current = detail.current.unwrap_or(layer.current);
+            layer.total = detail.total.unwrap_or(layer.total);
+        }
+
+        let fraction = if layer.total > 0 {
+            (layer.current as f64 / layer.total as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        // Pulls spend most of the time downloading, extraction is the tail end.
+        layer.progress = match status {
+            "Downloading" => 0.8 * fraction,
+            "Verifying Checksum" | "Download complete" => 0.8,
+            "Extracting" => 0.8 + 0.2 * fraction,
+            "Pull complete" | "Already exists" => 1.0,
+            _ => layer.progress,
+        };
+
+        changed
+    }
+
+    fn event(&self, operation_id: &str, reference: &str) -> ImageProgressEvent {
+        let percent = if self.layers.is_empty() {
+            0.0
+        } else {
+            self.layers.iter().map(|l| l.progress).sum::<f64>() / self.layers.len() as f64 * 100.0
+        };
+
+        ImageProgressEvent {
+            operation_id: operation_id.to_string(),
+            reference: reference.to_string(),
+            status: self.status.clone(),
+            layers: self.layers.clone(),
+            current: self.layers.iter().map(|l| l.current).sum(),
+            total: self.layers.iter().map(|l| l.total).sum(),
+            percent,
+        }
+    }
+
+    fn complete(&mut self) {
+        for layer in &mut self.layers {
+            layer.progress = 1.0;
+        }
+    }
+}
+
+// bollard's PushImageInfo drops the layer id the Engine sends, so uploads are told apart
+// by their size and completed layers are only counted against the image's layer total.
+struct PushProgressTracker {
+    status: String,
+    expected_layers: usize,
+    finished_layers: usize,
+    uploads: Vec<LayerProgress>,
+    throttle: EmitThrottle,
+}
+
+impl PushProgressTracker {
+    fn new(expected_layers: usize) -> Self {
+        Self {
+            status: String::new(),
+            expected_layers,
+            finished_layers: 0,
+            uploads: Vec::new(),
+            throttle: EmitThrottle::default(),
+        }
+    }
+
+    fn update(&mut self, status: Option<&str>, detail: Option<&ProgressDetail>) -> bool {
+        let status = status.unwrap_or_default();
+
+        match status {
+            "Pushing" => {
+                let Some(detail) = detail else {
+                    return false;
+                };
+                let total = detail.total.unwrap_or(0);
+                let current = detail.current.unwrap_or(0);
+
+                let index = match self.uploads.iter().position(|u| u.total == total && u.status == "Pushing") {
+                    Some(index) => index,
+                    None => {
+                        self.uploads.push(LayerProgress {
+                            id: format!("upload-{}", self.uploads.len() + 1),
+                            status: status.to_string(),
+                            current: 0,
+                            total,
+                            progress: 0.0,
+                        });
+                        self.uploads.len() - 1
+                    },
+                };
+
+                let upload = &mut self.uploads[index];
+                upload.current = current;
+                upload.progress = if total > 0 {
+                    (current as f64 / total as f64).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                false
+            },
+            "Pushed" | "Layer already exists" => {
+                self.finish_layer(status);
+                true
+            },
+            s if s.starts_with("Mounted from") => {
+                self.finish_layer(status);
+                true
+            },
+            "Preparing" | "Waiting" | "" => false,
+            other => {
+                self.status = other.to_string();
+                true
+            },
+        }
+    }
+
+    fn finish_layer(&mut self, status: &str) {
+        self.finished_layers += 1;
+
+        // Best guess at which upload this was: the most complete one still running.
+        let running = self.uploads.iter_mut()
+            .filter(|u| u.status == "Pushing")
+            .max_by(|a, b| a.progress.total_cmp(&b.progress));
+
+        if let Some(upload) = running.filter(|_| status == "Pushed") {
+            upload.status = status.to_string();
+            upload.progress = 1.0;
+        }
+    }
+
+    fn event(&self, operation_id: &str, reference: &str) -> ImageProgressEvent {
+        let running: f64 = self.uploads.iter()
+            .filter(|u| u.status == "Pushing")
+            .map(|u| u.progress)
+            .sum();
+        let expected = self.expected_layers.max(self.finished_layers).max(1);
+
+        ImageProgressEvent {
+            operation_id: operation_id.to_string(),
+            reference: reference.to_string(),
+            status: self.status.clone(),
+            layers: self.uploads.clone(),
+            current: self.uploads.iter().map(|u| u.current).sum(),
+            total: self.uploads.iter().map(|u| u.total).sum(),
+            percent: ((self.finished_layers as f64 + running) / expected as f64 * 100.0).min(100.0),
+        }
+    }
+
+    // Final status reads like `1.0: digest: sha256:... size: 1570`.
+    fn digest(&self) -> Option<String> {
+        let (_, rest) = self.status.split_once("digest: ")?;
+        rest.split_whitespace().next().map(|d| d.to_string())
+    }
+}
+
+// Stream messages with an id are mostly per-layer, except the leading
+// "Pulling from ..." line which carries the tag as its id.
+fn is_layer_status(status: &str) -> bool {
+    !status.starts_with("Pulling from") && !status.starts_with("Digest:") && !status.starts_with("Status:")
+}
+
+// `app:1.0@sha256:...` -> (`app:1.0`, Some(`sha256:...`))
+pub fn split_digest(reference: &str) -> (&str, Option<&str>) {
+    match reference.split_once('@') {
+        Some((name, digest)) => (name, Some(digest)),
+        None => (reference, None),
+    }
+}
+
+// `registry:5000/app:1.0` -> (`registry:5000/app`, Some(`1.0`)), a digest is ignored.
+pub fn split_tag(reference: &str) -> (String, Option<String>) {
+    let (reference, _) = split_digest(reference);
+    let name_start = reference.rfind('/').map(|i| i + 1).unwrap_or(0);
+
+    match reference[name_start..].rfind(':') {
+        Some(i) => {
+            let split = name_start + i;
+            (reference[..split].to_string(), Some(reference[split + 1..].to_string()))
+        },
+        None => (reference.to_string(), None),
+    }
+}
+
+// Without a tag the Engine API pulls every tag of the repository.
+pub fn normalize_reference(reference: &str) -> String {
+    let name = reference.rsplit('/').next().unwrap_or(reference);
+    if reference.contains('@') || name.contains(':') {
+        reference.to_string()
+    } else {
+        format!("{}:latest", reference)
+    }
+}
+
+#[tauri::command]
+pub async fn pull_image(
+    reference: String,
+    platform: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+    let reference = normalize_reference(&reference);
+
+    let options = CreateImageOptions {
+        from_image: Some(reference.clone()),
+        platform: platform.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let credentials = credentials_for(&reference).await;
+    let mut pull_stream = docker.create_image(Some(options), None, credentials);
+    let mut tracker = LayerProgressTracker::default();
+
+    loop {
+        let item = tokio::select! {
+            item = pull_stream.next() => item,
+            _ = &mut cancel_future => return Err("Pull cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| format!("Failed to pull image: {}", e))?;
+        let changed = tracker.update(info.id.as_deref(), info.status.as_deref(), info.progress_detail.as_ref());
+
+        if tracker.throttle.ready(changed) {
+            let _ = window.emit("image-pull-progress", tracker.event(&operation.id, &reference));
+        }
+    }
+
+    tracker.complete();
+    window.emit("image-pull-progress", tracker.event(&operation.id, &reference))
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn tag_image(
+    source: String,
+    repo: String,
+    tag: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    let options = TagImageOptions {
+        repo: Some(repo),
+        tag: Some(tag),
+    };
+
+    docker.tag_image(&source, Some(options))
+        .await
+        .map_err(|e| format!("Failed to tag image: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn untag_image(
+    reference: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    let image = docker.inspect_image(&reference)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+
+    // Deleting the last tag deletes the image itself, that is remove_image's job.
+    if image.repo_tags.unwrap_or_default().len() <= 1 {
+        return Err(format!("{} is the only tag of this image, remove the image instead", reference));
+    }
+
+    let options = RemoveImageOptions {
+        force: false,
+        noprune: true,
+    };
+
+    docker.remove_image(&reference, Some(options), None)
+        .await
+        .map_err(|e| format!("Failed to untag image: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn push_image(
+    reference: String,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<Option<String>, String> {
+    let docker = get_docker_connection(state)?;
+    let reference = normalize_reference(&reference);
+
+    // The Engine pushes tags, a digest names content that is already in a registry.
+    if split_digest(&reference).1.is_some() {
+        return Err(format!("Cannot push {}, push a tag instead of a digest", reference));
+    }
+    let (repo, tag) = split_tag(&reference);
+
+    let image = docker.inspect_image(&reference)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+    let layer_count = image.root_fs
+        .and_then(|fs| fs.layers)
+        .map(|layers| layers.len())
+        .unwrap_or(0);
+
+    let options = PushImageOptions {
+        tag,
+        ..Default::default()
+    };
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let credentials = credentials_for(&reference).await;
+    let mut push_stream = docker.push_image(&repo, Some(options), credentials);
+    let mut tracker = PushProgressTracker::new(layer_count);
+
+    loop {
+        let item = tokio::select! {
+            item = push_stream.next() => item,
+            _ = &mut cancel_future => return Err("Push cancelled".to_string()),
+        };
+
+        let Some(item) = item else {
+            break;
+        };
+
+        let info = item.map_err(|e| format!("Failed to push image: {}", e))?;
+        let changed = tracker.update(info.status.as_deref(), info.progress_detail.as_ref());
+
+        if tracker.throttle.ready(changed) {
+            let _ = window.emit("image-push-progress", tracker.event(&operation.id, &reference));
+        }
+    }
+
+    tracker.finished_layers = tracker.finished_layers.max(tracker.expected_layers);
+    window.emit("image-push-progress", tracker.event(&operation.id, &reference))
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(tracker.digest())
+}
+
+async fn image_containers(docker: &Docker, image_id: &str) -> Result<Vec<ImageContainerRef>, String> {
+    let options = ListContainersOptionsBuilder::default()
+        .all(true)
+        .build();
+
+    let containers = docker.list_containers(Some(options)).await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    Ok(containers.into_iter()
+        .filter(|c| c.image_id.as_deref() == Some(image_id))
+        .map(|c| {
+            let id = c.id.unwrap_or_default();
+            ImageContainerRef {
+                name: c.names.unwrap_or_default().first()
+                    .map(|n| n.trim_start_matches('/').to_string())
+                    .unwrap_or_else(|| id.clone()),
+                id: id.chars().take(12).collect(),
+                state: c.state.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_image_containers(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<Vec<ImageContainerRef>, String> {
+    let docker = get_docker_connection(state)?;
+
+    let image = docker.inspect_image(&id)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+
+    image_containers(&docker, &image.id.unwrap_or(id)).await
+}
+
+// Without `force`, an image still referenced by containers is not touched and the
+// containers are returned instead, so the UI can ask before retrying.
+#[tauri::command]
+pub async fn remove_image(
+    id: String,
+    force: Option<bool>,
+    noprune: Option<bool>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<RemoveImageResult, String> {
+    let docker = get_docker_connection(state)?;
+    let force = force.unwrap_or(false);
+
+    let image = docker.inspect_image(&id)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+    let containers = image_containers(&docker, image.id.as_deref().unwrap_or(&id)).await?;
+
+    // Removing one of several tags only untags, the containers keep running on the image.
+    let repo_tags = image.repo_tags.unwrap_or_default();
+    let is_tag = repo_tags.iter().any(|tag| *tag == id || *tag == normalize_reference(&id));
+    let deletes_image = !is_tag || repo_tags.len() <= 1;
+
+    if deletes_image && !containers.is_empty() && !force {
+        return Ok(RemoveImageResult {
+            removed: false,
+            containers,
+            untagged: Vec::new(),
+            deleted: Vec::new(),
+        });
+    }
+
+    let options = RemoveImageOptions {
+        force,
+        noprune: noprune.unwrap_or(false),
+    };
+
+    let items = docker.remove_image(&id, Some(options), None)
+        .await
+        .map_err(|e| format!("Failed to remove image: {}", e))?;
+
+    Ok(RemoveImageResult {
+        removed: true,
+        containers,
+        untagged: items.iter().filter_map(|i| i.untagged.clone()).collect(),
+        deleted: items.iter().filter_map(|i| i.deleted.clone()).collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn prune_images(
+    dangling_only: Option<bool>,
+    until: Option<String>,
+    labels: Option<Vec<String>>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<PruneImagesResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let mut filters: HashMap<&str, Vec<String>> = HashMap::new();
+    filters.insert("dangling", vec![dangling_only.unwrap_or(true).to_string()]);
+
+    if let Some(until) = until {
+        filters.insert("until", vec![until]);
+    }
+
+    // `!key=value` excludes images with that label.
+    for label in labels.unwrap_or_default() {
+        match label.strip_prefix('!') {
+            Some(label) => filters.entry("label!").or_default().push(label.to_string()),
+            None => filters.entry("label").or_default().push(label),
+        }
+    }
+
+    let options = PruneImagesOptionsBuilder::default()
+        .filters(&filters)
+        .build();
+
+    let response = docker.prune_images(Some(options))
+        .await
+        .map_err(|e| format!("Failed to prune images: {}", e))?;
+
+    let items = response.images_deleted.unwrap_or_default();
+
+    Ok(PruneImagesResult {
+        deleted: items.iter().filter_map(|i| i.deleted.clone()).collect(),
+        untagged: items.iter().filter_map(|i| i.untagged.clone()).collect(),
+        space_reclaimed: response.space_reclaimed.unwrap_or(0),
+    })
+}
+
+#[tauri::command]
+pub async fn get_image_details(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<ImageDetails, String> {
+    let docker = get_docker_connection(state)?;
+
+    let image = docker.inspect_image(&id)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+
+    // Newest layer first, the same order as `docker history`.
+    let history = docker.image_history(&id)
+        .await
+        .map_err(|e| format!("Failed to read image history: {}", e))?;
+
+    let layers = history.into_iter().map(|item| {
+        ImageLayer {
+            id: item.id,
+            created: item.created,
+            created_by: item.created_by,
+            size: item.size,
+            tags: item.tags,
+            comment: item.comment,
+        }
+    }).collect();
+
+    let config = image.config.unwrap_or_default();
+
+    let mut exposed_ports: Vec<String> = config.exposed_ports.unwrap_or_default().into_keys().collect();
+    exposed_ports.sort();
+
+    let mut volumes: Vec<String> = config.volumes.unwrap_or_default().into_keys().collect();
+    volumes.sort();
+
+    let details = ImageDetails {
+        id: image.id.unwrap_or(id),
+        repo_tags: image.repo_tags.unwrap_or_default(),
+        repo_digests: image.repo_digests.unwrap_or_default(),
+        parent: image.parent.unwrap_or_default(),
+        created: image.created.unwrap_or_default(),
+        author: image.author.unwrap_or_default(),
+        architecture: image.architecture.unwrap_or_default(),
+        variant: image.variant,
+        os: image.os.unwrap_or_default(),
+        size: image.size.unwrap_or(0),
+        entrypoint: config.entrypoint.unwrap_or_default(),
+        cmd: config.cmd.unwrap_or_default(),
+        env: config.env.unwrap_or_default(),
+        exposed_ports,
+        volumes,
+        labels: config.labels.unwrap_or_default(),
+        user: config.user.unwrap_or_default(),
+        working_dir: config.working_dir.unwrap_or_default(),
+        layers,
+    };
+
+    Ok(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::{CreateImageInfo, PushImageInfo};
+
+    fn pull(id: Option<&str>, status: &str, progress: Option<(i64, i64)>) -> CreateImageInfo {
+        CreateImageInfo {
+            id: id.map(String::from),
+            status: Some(status.to_string()),
+            progress_detail: progress.map(|(current, total)| ProgressDetail {
+                current: Some(current),
+                total: Some(total),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn track_pull(tracker: &mut LayerProgressTracker, messages: &[CreateImageInfo]) {
+        for info in messages {
+            tracker.update(info.id.as_deref(), info.status.as_deref(), info.progress_detail.as_ref());
+        }
+    }
+
+    #[test]
+    fn pull_counts_existing_layers_as_done() {
+        let mut tracker = LayerProgressTracker::default();
+        track_pull(&mut tracker, &[
+            pull(Some("latest"), "Pulling from library/nginx", None),
+            pull(Some("aaa"), "Already exists", None),
+            pull(Some("bbb"), "Pulling fs layer", None),
+            pull(Some("bbb"), "Downloading", Some((50, 100))),
+        ]);
+
+        let event = tracker.event("op", "nginx:latest");
+        assert_eq!(event.status, "Pulling from library/nginx");
+        assert_eq!(event.layers.len(), 2);
+        assert_eq!(event.layers[0].progress, 1.0);
+        assert!((event.percent - 70.0).abs() < 1e-9);
+        assert_eq!((event.current, event.total), (50, 100));
+    }
+
+    #[test]
+    fn pull_retries_restart_the_layer() {
+        let mut tracker = LayerProgressTracker::default();
+        track_pull(&mut tracker, &[
+            pull(Some("aaa"), "Downloading", Some((80, 100))),
+            pull(Some("aaa"), "Retrying in 5 seconds", None),
+        ]);
+        assert_eq!(tracker.layers[0].status, "Retrying in 5 seconds");
+        assert!((tracker.layers[0].progress - 0.64).abs() < 1e-9);
+
+        track_pull(&mut tracker, &[
+            pull(Some("aaa"), "Downloading", Some((10, 100))),
+        ]);
+        assert!((tracker.layers[0].progress - 0.08).abs() < 1e-9);
+
+        track_pull(&mut tracker, &[
+            pull(Some("aaa"), "Download complete", None),
+            pull(Some("aaa"), "Extracting", Some((50, 100))),
+            pull(Some("aaa"), "Pull complete", None),
+            pull(None, "Digest: sha256:abc", None),
+            pull(None, "Status: Downloaded newer image for nginx:latest", None),
+        ]);
+        let event = tracker.event("op", "nginx:latest");
+        assert_eq!(event.layers.len(), 1);
+        assert_eq!(event.percent, 100.0);
+        assert_eq!(event.status, "Status: Downloaded newer image for nginx:latest");
+    }
+
+    #[test]
+    fn pull_status_lines_are_not_layers() {
+        let mut tracker = LayerProgressTracker::default();
+        assert!(tracker.update(Some("1.25"), Some("Pulling from library/nginx"), None));
+        assert!(tracker.update(Some("aaa"), Some("Waiting"), None));
+        assert!(!tracker.update(Some("aaa"), Some("Waiting"), None));
+        assert_eq!(tracker.layers.len(), 1);
+
+        tracker.complete();
+        assert_eq!(tracker.event("op", "nginx:1.25").percent, 100.0);
+    }
+
+    fn push(status: &str, progress: Option<(i64, i64)>) -> PushImageInfo {
+        PushImageInfo {
+            status: Some(status.to_string()),
+            progress_detail: progress.map(|(current, total)| ProgressDetail {
+                current: Some(current),
+                total: Some(total),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn track_push(tracker: &mut PushProgressTracker, messages: &[PushImageInfo]) {
+        for info in messages {
+            tracker.update(info.status.as_deref(), info.progress_detail.as_ref());
+        }
+    }
+
+    #[test]
+    fn push_counts_existing_and_mounted_layers() {
+        let mut tracker = PushProgressTracker::new(4);
+        track_push(&mut tracker, &[
+            push("The push refers to repository [registry:5000/app]", None),
+            push("Preparing", None),
+            push("Layer already exists", None),
+            push("Mounted from library/alpine", None),
+            push("Pushing", Some((25, 100))),
+        ]);
+
+        let event = tracker.event("op", "registry:5000/app:1.0");
+        assert_eq!(event.status, "The push refers to repository [registry:5000/app]");
+        assert_eq!(tracker.finished_layers, 2);
+        assert_eq!(event.layers.len(), 1);
+        assert!((event.percent - 56.25).abs() < 1e-9);
+
+        track_push(&mut tracker, &[
+            push("Pushed", None),
+            push("Pushing", Some((10, 10))),
+            push("Pushed", None),
+            push("1.0: digest: sha256:abc size: 1570", None),
+        ]);
+        let event = tracker.event("op", "registry:5000/app:1.0");
+        assert_eq!(event.percent, 100.0);
+        assert!(event.layers.iter().all(|u| u.status == "Pushed"));
+        assert_eq!(tracker.digest().as_deref(), Some("sha256:abc"));
+    }
+
+    #[test]
+    fn push_retries_reuse_the_upload() {
+        let mut tracker = PushProgressTracker::new(1);
+        track_push(&mut tracker, &[
+            push("Pushing", Some((60, 200))),
+            push("Retrying in 5 seconds", None),
+            push("Pushing", Some((20, 200))),
+        ]);
+
+        assert_eq!(tracker.uploads.len(), 1);
+        assert_eq!(tracker.uploads[0].current, 20);
+        assert!((tracker.event("op", "app:1.0").percent - 10.0).abs() < 1e-9);
+
+        track_push(&mut tracker, &[push("Pushed", None)]);
+        assert_eq!(tracker.event("op", "app:1.0").percent, 100.0);
+        assert_eq!(tracker.digest(), None);
+    }
+
+    #[test]
+    fn push_progress_never_exceeds_the_layer_count() {
+        let mut tracker = PushProgressTracker::new(1);
+        track_push(&mut tracker, &[
+            push("Layer already exists", None),
+            push("Layer already exists", None),
+            push("Mounted from org/base", None),
+        ]);
+
+        assert_eq!(tracker.event("op", "app:1.0").percent, 100.0);
+    }
+
+    #[test]
+    fn splits_tags() {
+        assert_eq!(split_tag("host:5000/repo"), ("host:5000/repo".to_string(), None));
+        assert_eq!(split_tag("host:5000/repo:1.0"), ("host:5000/repo".to_string(), Some("1.0".to_string())));
+        assert_eq!(split_tag("repo:tag"), ("repo".to_string(), Some("tag".to_string())));
+        assert_eq!(split_tag("repo@sha256:abc"), ("repo".to_string(), None));
+        assert_eq!(split_tag("repo:tag@sha256:abc"), ("repo".to_string(), Some("tag".to_string())));
+    }
+
+    #[test]
+    fn splits_digests() {
+        assert_eq!(split_digest("repo@sha256:abc"), ("repo", Some("sha256:abc")));
+        assert_eq!(split_digest("host:5000/repo:tag"), ("host:5000/repo:tag", None));
+    }
+
+    #[test]
+    fn normalizes_references() {
+        assert_eq!(normalize_reference("host:5000/repo"), "host:5000/repo:latest");
+        assert_eq!(normalize_reference("repo:tag"), "repo:tag");
+        assert_eq!(normalize_reference("repo@sha256:abc"), "repo@sha256:abc");
+        assert_eq!(normalize_reference("nginx"), "nginx:latest");
+    }
+}
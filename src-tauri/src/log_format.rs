@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const TIMESTAMP_KEYS: [&str; 5] = ["time", "timestamp", "ts", "@timestamp", "datetime"];
+const LEVEL_KEYS: [&str; 5] = ["level", "lvl", "severity", "log.level", "loglevel"];
+const MESSAGE_KEYS: [&str; 4] = ["msg", "message", "@message", "text"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StructuredLog {
+    pub timestamp: Option<String>,
+    pub level: Option<String>,
+    pub message: Option<String>,
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldFilter {
+    key: String,
+    value: String,
+    negate: bool,
+}
+
+pub fn normalize_level(word: &str) -> Option<String> {
+    let level = match word.to_ascii_uppercase().as_str() {
+        "ERROR" | "ERR" | "FATAL" | "CRITICAL" | "CRIT" | "PANIC" => "ERROR",
+        "WARN" | "WARNING" => "WARN",
+        "INFO" | "NOTICE" => "INFO",
+        "DEBUG" => "DEBUG",
+        "TRACE" => "TRACE",
+        _ => return None,
+    };
+    Some(level.to_string())
+}
+
+// pino/bunyan style numeric levels
+fn numeric_level(value: u64) -> String {
+    let level = match value {
+        50.. => "ERROR",
+        40..=49 => "WARN",
+        30..=39 => "INFO",
+        20..=29 => "DEBUG",
+        _ => "TRACE",
+    };
+    level.to_string()
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+// Splits `key=value key2="quoted value"` into pairs. Returns None as soon as a token
+// is not a pair, so ordinary text is not mistaken for logfmt.
+fn split_pairs(input: &str) -> Option<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut chars = input.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            if c == '=' || c.is_whitespace() {
+                break;
+            }
+            key.push(c);
+            chars.next();
+        }
+
+        if key.is_empty() || chars.next() != Some('=') {
+            return None;
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => value.push(chars.next()?),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+
+        pairs.push((key, value));
+
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+    }
+
+    Some(pairs)
+}
+
+fn parse_json(line: &str) -> Option<StructuredLog> {
+    let Ok(Value::Object(object)) = serde_json::from_str::<Value>(line) else {
+        return None;
+    };
+
+    let mut structured = StructuredLog::default();
+
+    for (key, value) in object {
+        if structured.timestamp.is_none() && TIMESTAMP_KEYS.contains(&key.as_str()) {
+            structured.timestamp = Some(value_to_string(&value));
+        } else if structured.level.is_none() && LEVEL_KEYS.contains(&key.as_str()) {
+            structured.level = match value {
+                Value::Number(ref n) => Some(numeric_level(n.as_u64().unwrap_or(0))),
+                ref other => normalize_level(&value_to_string(other)),
+            };
+        } else if structured.message.is_none() && MESSAGE_KEYS.contains(&key.as_str()) {
+            structured.message = Some(value_to_string(&value));
+        } else {
+            structured.fields.insert(key, value_to_string(&value));
+        }
+    }
+
+    Some(structured)
+}
+
+fn parse_logfmt(line: &str) -> Option<StructuredLog> {
+    let pairs = split_pairs(line)?;
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let mut structured = StructuredLog::default();
+
+    for (key, value) in pairs {
+        if structured.timestamp.is_none() && TIMESTAMP_KEYS.contains(&key.as_str()) {
+            structured.timestamp = Some(value);
+        } else if structured.level.is_none() && LEVEL_KEYS.contains(&key.as_str()) {
+            structured.level = normalize_level(&value);
+        } else if structured.message.is_none() && MESSAGE_KEYS.contains(&key.as_str()) {
+            structured.message = Some(value);
+        } else {
+            structured.fields.insert(key, value);
+        }
+    }
+
+    Some(structured)
+}
+
+pub fn parse_structured(line: &str) -> Option<StructuredLog> {
+    let trimmed = line.trim();
+
+    if trimmed.starts_with('{') {
+        parse_json(trimmed)
+    } else {
+        parse_logfmt(trimmed)
+    }
+}
+
+pub fn detect_level(line: &str, structured: Option<&StructuredLog>) -> Option<String> {
+    if let Some(level) = structured.and_then(|s| s.level.clone()) {
+        return Some(level);
+    }
+
+    // Only upper-case words count in free text, "no error found" is not an error line.
+    line.split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| word.len() >= 3 && word.chars().all(|c| c.is_ascii_uppercase()))
+        .find_map(normalize_level)
+}
+
+// Parses filters like `level=error service=api user!=admin`.
+pub fn parse_field_filters(expression: &str) -> Result<Vec<FieldFilter>, String> {
+    let pairs = split_pairs(expression)
+        .ok_or_else(|| format!("Invalid field filter: {}", expression))?;
+
+    Ok(pairs.into_iter().map(|(key, value)| {
+        match key.strip_suffix('!') {
+            Some(key) => FieldFilter { key: key.to_string(), value, negate: true },
+            None => FieldFilter { key, value, negate: false },
+        }
+    }).collect())
+}
+
+pub fn matches_field_filters(
+    filters: &[FieldFilter],
+    stream: &str,
+    level: Option<&str>,
+    structured: Option<&StructuredLog>,
+) -> bool {
+    filters.iter().all(|filter| {
+        let matched = match filter.key.as_str() {
+            "level" => {
+                let wanted = normalize_level(&filter.value).unwrap_or_else(|| filter.value.to_ascii_uppercase());
+                level == Some(wanted.as_str())
+            },
+            "stream" => stream.eq_ignore_ascii_case(&filter.value),
+            "msg" | "message" => structured
+                .and_then(|s| s.message.as_deref())
+                .is_some_and(|m| m.eq_ignore_ascii_case(&filter.value)),
+            key => structured
+                .and_then(|s| s.fields.get(key))
+                .is_some_and(|v| v.eq_ignore_ascii_case(&filter.value)),
+        };
+
+        matched != filter.negate
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_lines() {
+        let line = r#"{"time":"2024-05-01T10:00:00Z","level":"warning","msg":"slow query","took":1.5,"ctx":{"user":"alice","ids":[1,2]}}"#;
+        let parsed = parse_structured(line).unwrap();
+
+        assert_eq!(parsed.timestamp.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(parsed.level.as_deref(), Some("WARN"));
+        assert_eq!(parsed.message.as_deref(), Some("slow query"));
+        assert_eq!(parsed.fields["took"], "1.5");
+        // Nested values are kept as compact JSON.
+        assert_eq!(parsed.fields["ctx"], r#"{"ids":[1,2],"user":"alice"}"#);
+    }
+
+    #[test]
+    fn parses_numeric_json_levels() {
+        let parsed = parse_structured(r#"{"level":50,"msg":"boom"}"#).unwrap();
+        assert_eq!(parsed.level.as_deref(), Some("ERROR"));
+
+        let parsed = parse_structured(r#"{"level":30,"msg":"ok"}"#).unwrap();
+        assert_eq!(parsed.level.as_deref(), Some("INFO"));
+    }
+
+    #[test]
+    fn rejects_non_object_json() {
+        assert!(parse_structured(r#"{"unterminated": "#).is_none());
+        assert!(parse_structured("[1, 2, 3]").is_none());
+    }
+
+    #[test]
+    fn parses_logfmt_with_quoted_values() {
+        let line = r#"ts=2024-05-01T10:00:00Z level=error msg="connection refused: \"db\" unreachable" retry=3 path="""#;
+        let parsed = parse_structured(line).unwrap();
+
+        assert_eq!(parsed.timestamp.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(parsed.level.as_deref(), Some("ERROR"));
+        assert_eq!(parsed.message.as_deref(), Some(r#"connection refused: "db" unreachable"#));
+        assert_eq!(parsed.fields["retry"], "3");
+        assert_eq!(parsed.fields["path"], "");
+    }
+
+    #[test]
+    fn plain_text_is_not_logfmt() {
+        assert!(parse_structured("Starting server on port 8080").is_none());
+        assert!(parse_structured("a=1 and then some text").is_none());
+        assert!(parse_structured(r#"msg="unterminated level=info"#).is_none());
+        // A single pair is more likely text than a structured line.
+        assert!(parse_structured("answer=42").is_none());
+    }
+
+    #[test]
+    fn detects_levels_in_free_text() {
+        assert_eq!(detect_level("2024/05/01 ERROR failed to bind", None).as_deref(), Some("ERROR"));
+        assert_eq!(detect_level("[WARNING] disk almost full", None).as_deref(), Some("WARN"));
+        assert_eq!(detect_level("no error found", None), None);
+        assert_eq!(detect_level("OK", None), None);
+
+        let structured = parse_structured("level=debug msg=hello").unwrap();
+        assert_eq!(detect_level("ERROR in message text", Some(&structured)).as_deref(), Some("DEBUG"));
+    }
+
+    #[test]
+    fn field_filters_match_and_negate() {
+        let structured = parse_structured(r#"level=info msg="request done" service=api user=Admin"#).unwrap();
+        let level = detect_level("", Some(&structured));
+        let matches = |expression: &str| {
+            let filters = parse_field_filters(expression).unwrap();
+            matches_field_filters(&filters, "stdout", level.as_deref(), Some(&structured))
+        };
+
+        assert!(matches("level=info service=api"));
+        assert!(matches("level=notice"));
+        assert!(matches(r#"msg="REQUEST DONE""#));
+        assert!(matches("stream=stdout"));
+        assert!(!matches("service=web"));
+        assert!(!matches("user!=admin"));
+        assert!(matches("user!=root"));
+        // A missing field never equals the value, so its negation matches.
+        assert!(!matches("region=eu"));
+        assert!(matches("region!=eu"));
+    }
+
+    #[test]
+    fn rejects_invalid_filter_expressions() {
+        assert!(parse_field_filters("level").is_err());
+        assert!(parse_field_filters(r#"msg="open"#).is_err());
+    }
+}
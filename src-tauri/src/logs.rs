@@ -0,0 +1,635 @@
+use bollard::container::LogOutput;
+use bollard::query_parameters::{
+    InspectContainerOptions,
+    ListContainersOptionsBuilder,
+    LogsOptions,
+};
+use bollard::Docker;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::{Regex, RegexBuilder};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::sync::oneshot;
+
+use crate::log_format::{
+    detect_level,
+    matches_field_filters,
+    normalize_level,
+    parse_field_filters,
+    parse_structured,
+    FieldFilter,
+    StructuredLog,
+};
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogLine {
+    pub container_id: String,
+    pub stream: String,
+    pub timestamp: Option<String>,
+    pub level: Option<String>,
+    pub line: String,
+    pub structured: Option<StructuredLog>,
+}
+
+impl LogLine {
+    fn matches_fields(&self, filters: &[FieldFilter]) -> bool {
+        matches_field_filters(filters, &self.stream, self.level.as_deref(), self.structured.as_ref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogEvent {
+    pub subscription_id: u64,
+    #[serde(flatten)]
+    pub line: LogLine,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AggregatedLogEvent {
+    pub subscription_id: u64,
+    pub container_name: String,
+    #[serde(flatten)]
+    pub line: LogLine,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LogExportFormat {
+    Text,
+    Jsonl,
+    Gzip,
+}
+
+// Lines kept per subscription for search after they have been pushed to the UI.
+const LOG_BUFFER_CAPACITY: usize = 10_000;
+
+struct LogSubscription {
+    window_label: String,
+    // Taken once the stream has finished, the buffer stays searchable until stopped.
+    cancel: Option<oneshot::Sender<()>>,
+    buffer: VecDeque<LogLine>,
+}
+
+struct LogFilter {
+    pattern: Option<Regex>,
+    levels: Vec<String>,
+    stream: Option<String>,
+    fields: Vec<FieldFilter>,
+}
+
+impl LogFilter {
+    fn matches(&self, line: &LogLine) -> bool {
+        if let Some(ref stream) = self.stream {
+            if &line.stream != stream {
+                return false;
+            }
+        }
+
+        if !self.levels.is_empty() {
+            match line.level {
+                Some(ref level) if self.levels.contains(level) => {},
+                _ => return false,
+            }
+        }
+
+        if !line.matches_fields(&self.fields) {
+            return false;
+        }
+
+        match self.pattern {
+            Some(ref pattern) => pattern.is_match(&line.line),
+            None => true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LogSubscriptions {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<u64, LogSubscription>>,
+}
+
+impl LogSubscriptions {
+    fn register(&self, window_label: &str) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (cancel, cancel_rx) = oneshot::channel();
+
+        self.active.lock().unwrap().insert(id, LogSubscription {
+            window_label: window_label.to_string(),
+            cancel: Some(cancel),
+            buffer: VecDeque::new(),
+        });
+
+        (id, cancel_rx)
+    }
+
+    fn record(&self, id: u64, line: &LogLine) {
+        if let Some(subscription) = self.active.lock().unwrap().get_mut(&id) {
+            if subscription.buffer.len() == LOG_BUFFER_CAPACITY {
+                subscription.buffer.pop_front();
+            }
+            subscription.buffer.push_back(line.clone());
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        if let Some(subscription) = self.active.lock().unwrap().get_mut(&id) {
+            subscription.cancel = None;
+        }
+    }
+
+    fn search(&self, id: u64, filter: &LogFilter, limit: usize) -> Option<Vec<LogLine>> {
+        let active = self.active.lock().unwrap();
+        let subscription = active.get(&id)?;
+
+        let mut found: Vec<LogLine> = subscription.buffer.iter()
+            .rev()
+            .filter(|line| filter.matches(line))
+            .take(limit)
+            .cloned()
+            .collect();
+        found.reverse();
+
+        Some(found)
+    }
+
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.active.lock().unwrap().remove(&id) {
+            Some(subscription) => {
+                if let Some(cancel) = subscription.cancel {
+                    let _ = cancel.send(());
+                }
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn cancel_for_window(&self, window_label: &str) {
+        let ids: Vec<u64> = self.active.lock().unwrap()
+            .iter()
+            .filter(|(_, s)| s.window_label == window_label)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in ids {
+            self.cancel(id);
+        }
+    }
+}
+
+// Docker prefixes every line with an RFC3339Nano timestamp when `timestamps` is set.
+// Trailing zeros are trimmed there, so we re-format to a fixed width to keep the
+// string sortable on the frontend.
+fn split_timestamp(line: &str) -> (Option<DateTime<Utc>>, &str) {
+    if let Some((prefix, rest)) = line.split_once(' ') {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(prefix) {
+            return (Some(ts.with_timezone(&Utc)), rest);
+        }
+    }
+    (None, line)
+}
+
+fn parse_log_output(container_id: &str, output: LogOutput, timestamps: bool) -> Vec<LogLine> {
+    let (stream, message) = match output {
+        LogOutput::StdOut { message } | LogOutput::Console { message } => ("stdout", message),
+        LogOutput::StdErr { message } => ("stderr", message),
+        LogOutput::StdIn { .. } => return Vec::new(),
+    };
+
+    let text = String::from_utf8_lossy(&message);
+
+    text.split_terminator('\n')
+        .map(|raw| {
+            let raw = raw.strip_suffix('\r').unwrap_or(raw);
+            let (timestamp, line) = if timestamps {
+                split_timestamp(raw)
+            } else {
+                (None, raw)
+            };
+
+            let structured = parse_structured(line);
+
+            LogLine {
+                container_id: container_id.to_string(),
+                stream: stream.to_string(),
+                timestamp: timestamp.map(|ts| ts.to_rfc3339_opts(SecondsFormat::Nanos, true)),
+                level: detect_level(line, structured.as_ref()),
+                line: line.to_string(),
+                structured,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_container_logs(
+    id: String,
+    tail: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+    follow: Option<bool>,
+    timestamps: Option<bool>,
+    field_filter: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    subscriptions: tauri::State<'_, LogSubscriptions>
+) -> Result<u64, String> {
+    let docker = get_docker_connection(state)?;
+
+    let timestamps = timestamps.unwrap_or(true);
+    let field_filters = match field_filter {
+        Some(expression) => parse_field_filters(&expression)?,
+        None => Vec::new(),
+    };
+
+    let options = LogsOptions {
+        follow: follow.unwrap_or(true),
+        stdout: true,
+        stderr: true,
+        since: since.map(clamp_unix_seconds).unwrap_or(0),
+        until: until.map(clamp_unix_seconds).unwrap_or(0),
+        timestamps,
+        tail: tail.unwrap_or_else(|| "100".to_string()),
+    };
+
+    let (subscription_id, cancel_rx) = subscriptions.register(window.label());
+
+    tauri::async_runtime::spawn(async move {
+        let mut log_stream = docker.logs(&id, Some(options));
+        let mut cancel_future = Box::pin(cancel_rx);
+
+        'stream: while let Some(item) = tokio::select! {
+            item = log_stream.next() => item,
+            _ = &mut cancel_future => None,
+        } {
+            match item {
+                Ok(msg) => {
+                    for line in parse_log_output(&id, msg, timestamps) {
+                        window.state::<LogSubscriptions>().record(subscription_id, &line);
+                        if !line.matches_fields(&field_filters) {
+                            continue;
+                        }
+
+                        let event = LogEvent { subscription_id, line };
+                        if let Err(e) = window.emit("log-update", event) {
+                            eprintln!("Failed to emit log: {}", e);
+                            break 'stream;
+                        }
+                    }
+                },
+                Err(e) => {
+                    eprintln!("Log stream error: {}", e);
+                    break;
+                }
+            }
+        }
+
+        window.state::<LogSubscriptions>().finish(subscription_id);
+        let _ = window.emit("log-stream-ended", subscription_id);
+    });
+
+    Ok(subscription_id)
+}
+
+#[tauri::command]
+pub async fn stop_log_stream(
+    id: u64,
+    subscriptions: tauri::State<'_, LogSubscriptions>
+) -> Result<(), String> {
+    subscriptions.cancel(id);
+    Ok(())
+}
+
+// Lines from different containers arrive slightly out of order, so they are held
+// for a short window and released sorted by timestamp.
+const REORDER_WINDOW: Duration = Duration::from_millis(500);
+const REORDER_TICK: Duration = Duration::from_millis(100);
+const REORDER_BUFFER_LIMIT: usize = 10_000;
+
+struct LogSource {
+    id: String,
+    name: String,
+}
+
+struct BufferedLine {
+    arrived: Instant,
+    container_name: String,
+    line: LogLine,
+}
+
+async fn resolve_log_sources(
+    docker: &Docker,
+    containers: Vec<String>,
+    project: Option<String>,
+    labels: Vec<String>,
+) -> Result<Vec<LogSource>, String> {
+    let mut sources: Vec<LogSource> = Vec::new();
+
+    for container in containers {
+        let info = docker.inspect_container(&container, None::<InspectContainerOptions>).await
+            .map_err(|e| format!("Failed to inspect container {}: {}", container, e))?;
+
+        sources.push(LogSource {
+            id: info.id.unwrap_or_else(|| container.clone()),
+            name: info.name.map(|n| n.trim_start_matches('/').to_string()).unwrap_or(container),
+        });
+    }
+
+    let mut label_filters = labels;
+    if let Some(project) = project {
+        label_filters.push(format!("com.docker.compose.project={}", project));
+    }
+
+    if !label_filters.is_empty() {
+        let filters = HashMap::from([("label", label_filters)]);
+        let options = ListContainersOptionsBuilder::default()
+            .all(true)
+            .filters(&filters)
+            .build();
+
+        let listed = docker.list_containers(Some(options)).await
+            .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+        for c in listed {
+            let id = c.id.unwrap_or_default();
+            if sources.iter().any(|s| s.id == id) {
+                continue;
+            }
+
+            let name = c.names.unwrap_or_default().first()
+                .map(|n| n.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| id.clone());
+
+            sources.push(LogSource { id, name });
+        }
+    }
+
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(sources)
+}
+
+fn flush_reordered(
+    window: &tauri::Window,
+    subscription_id: u64,
+    buffer: &mut BTreeMap<(String, u64), BufferedLine>,
+    drain: bool,
+) -> Result<(), tauri::Error> {
+    let now = Instant::now();
+
+    loop {
+        let overflow = buffer.len() > REORDER_BUFFER_LIMIT;
+        let Some(entry) = buffer.first_entry() else {
+            break;
+        };
+
+        if !drain && !overflow && now.duration_since(entry.get().arrived) < REORDER_WINDOW {
+            break;
+        }
+
+        let buffered = entry.remove();
+        window.state::<LogSubscriptions>().record(subscription_id, &buffered.line);
+        window.emit("aggregated-log-update", AggregatedLogEvent {
+            subscription_id,
+            container_name: buffered.container_name,
+            line: buffered.line,
+        })?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_aggregated_logs(
+    containers: Option<Vec<String>>,
+    project: Option<String>,
+    labels: Option<Vec<String>>,
+    tail: Option<String>,
+    since: Option<i64>,
+    follow: Option<bool>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    subscriptions: tauri::State<'_, LogSubscriptions>
+) -> Result<u64, String> {
+    let docker = get_docker_connection(state)?;
+
+    let sources = resolve_log_sources(
+        &docker,
+        containers.unwrap_or_default(),
+        project,
+        labels.unwrap_or_default(),
+    ).await?;
+
+    if sources.is_empty() {
+        return Err("No containers matched the selection".to_string());
+    }
+
+    // Timestamps are always requested here, they are the merge key.
+    let options = LogsOptions {
+        follow: follow.unwrap_or(true),
+        stdout: true,
+        stderr: true,
+        since: since.map(clamp_unix_seconds).unwrap_or(0),
+        until: 0,
+        timestamps: true,
+        tail: tail.unwrap_or_else(|| "100".to_string()),
+    };
+
+    let (subscription_id, cancel_rx) = subscriptions.register(window.label());
+
+    tauri::async_runtime::spawn(async move {
+        let streams = sources.iter().map(|source| {
+            docker.logs(&source.id, Some(options.clone()))
+                .map(move |item| (source, item))
+                .boxed()
+        });
+
+        let mut merged = futures_util::stream::select_all(streams);
+        let mut buffer: BTreeMap<(String, u64), BufferedLine> = BTreeMap::new();
+        let mut seq = 0u64;
+        let mut ticker = tokio::time::interval(REORDER_TICK);
+        let mut cancel_future = Box::pin(cancel_rx);
+        let mut cancelled = false;
+
+        loop {
+            tokio::select! {
+                item = merged.next() => match item {
+                    Some((source, Ok(msg))) => {
+                        for line in parse_log_output(&source.id, msg, true) {
+                            // Timestamps are fixed-width RFC3339 in UTC, so they sort as strings.
+                            let key = line.timestamp.clone()
+                                .unwrap_or_else(|| Utc::now().to_rfc3339_opts(SecondsFormat::Nanos, true));
+                            seq += 1;
+
+                            buffer.insert((key, seq), BufferedLine {
+                                arrived: Instant::now(),
+                                container_name: source.name.clone(),
+                                line,
+                            });
+                        }
+                    },
+                    Some((source, Err(e))) => {
+                        eprintln!("Log stream error for {}: {}", source.name, e);
+                    },
+                    None => break,
+                },
+                _ = ticker.tick() => {},
+                _ = &mut cancel_future => {
+                    cancelled = true;
+                    break;
+                }
+            }
+
+            if let Err(e) = flush_reordered(&window, subscription_id, &mut buffer, false) {
+                eprintln!("Failed to emit log: {}", e);
+                cancelled = true;
+                break;
+            }
+        }
+
+        if !cancelled {
+            if let Err(e) = flush_reordered(&window, subscription_id, &mut buffer, true) {
+                eprintln!("Failed to emit log: {}", e);
+            }
+        }
+
+        window.state::<LogSubscriptions>().finish(subscription_id);
+        let _ = window.emit("log-stream-ended", subscription_id);
+    });
+
+    Ok(subscription_id)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_logs(
+    subscription_id: u64,
+    query: Option<String>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    levels: Option<Vec<String>>,
+    stream: Option<String>,
+    field_filter: Option<String>,
+    limit: Option<usize>,
+    subscriptions: tauri::State<'_, LogSubscriptions>
+) -> Result<Vec<LogLine>, String> {
+    let pattern = match query.filter(|q| !q.is_empty()) {
+        Some(query) => {
+            let source = if regex.unwrap_or(false) { query } else { regex::escape(&query) };
+            let pattern = RegexBuilder::new(&source)
+                .case_insensitive(!case_sensitive.unwrap_or(false))
+                .build()
+                .map_err(|e| format!("Invalid search pattern: {}", e))?;
+            Some(pattern)
+        },
+        None => None,
+    };
+
+    let filter = LogFilter {
+        pattern,
+        levels: levels.unwrap_or_default()
+            .iter()
+            .filter_map(|level| normalize_level(level))
+            .collect(),
+        stream,
+        fields: match field_filter {
+            Some(expression) => parse_field_filters(&expression)?,
+            None => Vec::new(),
+        },
+    };
+
+    subscriptions.search(subscription_id, &filter, limit.unwrap_or(LOG_BUFFER_CAPACITY))
+        .ok_or_else(|| format!("Log stream {} not found", subscription_id))
+}
+
+async fn write_log_export<W: Write>(
+    docker: &Docker,
+    id: &str,
+    options: LogsOptions,
+    format: LogExportFormat,
+    writer: &mut W,
+) -> Result<u64, String> {
+    let mut log_stream = docker.logs(id, Some(options));
+    let mut written = 0u64;
+
+    while let Some(item) = log_stream.next().await {
+        let msg = item.map_err(|e| format!("Failed to read logs: {}", e))?;
+
+        for line in parse_log_output(id, msg, true) {
+            match format {
+                LogExportFormat::Jsonl => {
+                    serde_json::to_writer(&mut *writer, &line)
+                        .map_err(|e| format!("Failed to write logs: {}", e))?;
+                    writer.write_all(b"\n")
+                },
+                LogExportFormat::Text | LogExportFormat::Gzip => {
+                    writeln!(writer, "{} {}", line.timestamp.as_deref().unwrap_or("-"), line.line)
+                },
+            }
+            .map_err(|e| format!("Failed to write logs: {}", e))?;
+
+            written += 1;
+        }
+    }
+
+    Ok(written)
+}
+
+#[tauri::command]
+pub async fn export_logs(
+    container: String,
+    since: Option<i64>,
+    until: Option<i64>,
+    format: LogExportFormat,
+    path: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<u64, String> {
+    let docker = get_docker_connection(state)?;
+
+    let options = LogsOptions {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        since: since.map(clamp_unix_seconds).unwrap_or(0),
+        until: until.map(clamp_unix_seconds).unwrap_or(0),
+        timestamps: true,
+        tail: "all".to_string(),
+    };
+
+    let file = File::create(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut writer = BufWriter::new(file);
+
+    let written = match format {
+        LogExportFormat::Gzip => {
+            let mut encoder = GzEncoder::new(writer, Compression::default());
+            let written = write_log_export(&docker, &container, options, format, &mut encoder).await?;
+            writer = encoder.finish()
+                .map_err(|e| format!("Failed to compress logs: {}", e))?;
+            written
+        },
+        _ => write_log_export(&docker, &container, options, format, &mut writer).await?,
+    };
+
+    writer.flush()
+        .map_err(|e| format!("Failed to write logs: {}", e))?;
+
+    Ok(written)
+}
+
+// The Engine API takes since/until as 32-bit unix seconds.
+fn clamp_unix_seconds(value: i64) -> i32 {
+    value.clamp(0, i32::MAX as i64) as i32
+}
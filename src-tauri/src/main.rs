@@ -1,8 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use bollard::container::LogOutput;
 use bollard::query_parameters::{
-    LogsOptions, 
     ListContainersOptionsBuilder, 
     StartContainerOptionsBuilder, 
     StopContainerOptionsBuilder,
@@ -15,11 +13,32 @@ use bollard::query_parameters::{
 };
 use bollard::Docker;
 use serde::{Deserialize, Serialize};
-use tokio::sync::oneshot;
 use futures_util::StreamExt; 
-use tauri::Emitter; 
 use std::default::Default;
 use std::sync::Mutex;
+use tauri::Manager;
+
+mod container_recreate;
+mod credentials;
+mod file_browser;
+mod image_archive;
+mod image_build;
+mod image_layers;
+mod image_transfer;
+mod image_updates;
+mod images;
+mod log_format;
+mod logs;
+mod network_topology;
+mod networks;
+mod operations;
+mod packages;
+mod registry;
+mod registry_browser;
+mod sbom;
+mod volume_backup;
+mod volumes;
+mod vulnerabilities;
 
 struct DockerConnection {
     connection_type: Mutex<ConnectionType>,
@@ -101,12 +120,25 @@ pub struct VolumeMount {
     pub rw: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContainerNetwork {
+    pub name: String,
+    pub network_id: String,
+    pub ip_address: Option<String>,
+    pub ip_prefix_len: Option<i64>,
+    pub gateway: Option<String>,
+    pub ipv6_address: Option<String>,
+    pub ipv6_gateway: Option<String>,
+    pub mac_address: Option<String>,
+    pub aliases: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ContainerDetails {
     pub env: Vec<String>,
     pub ports: Vec<PortMapping>,
     pub volumes: Vec<VolumeMount>,
-    pub networks: Vec<String>,
+    pub networks: Vec<ContainerNetwork>,
     pub hostname: String,
     pub image_id: String,
     pub created: String,
@@ -151,6 +183,78 @@ fn get_docker_connection(state: tauri::State<DockerConnection>) -> Result<Docker
     }
 }
 
+// The socket `Docker::connect_with_local_defaults` connects to.
+fn local_socket() -> String {
+    let (scheme, default) = if cfg!(windows) {
+        ("npipe://", "npipe:////./pipe/docker_engine")
+    } else {
+        ("unix://", "unix:///var/run/docker.sock")
+    };
+
+    std::env::var("DOCKER_HOST").ok()
+        .filter(|host| host.starts_with(scheme))
+        .unwrap_or_else(|| default.to_string())
+}
+
+// The address a host string connects to, so different spellings of one daemon compare equal.
+fn host_address(host: &str) -> String {
+    if host == "local" {
+        return local_socket();
+    }
+
+    if host.starts_with("unix://") || host.starts_with("npipe://") {
+        return host.to_string();
+    }
+
+    let address = host.strip_prefix("tcp://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host)
+        .trim_end_matches('/')
+        .to_lowercase();
+
+    // `[::1]` has colons but no port, only trailing digits after the last colon are one.
+    let has_port = address.rsplit_once(':')
+        .is_some_and(|(_, port)| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()));
+
+    if has_port {
+        format!("http://{}", address)
+    } else {
+        format!("http://{}:2375", address)
+    }
+}
+
+// The address of the active connection, in the same form as host_address.
+fn connection_address(state: &tauri::State<DockerConnection>) -> String {
+    let conn_type = state.connection_type.lock().unwrap().clone();
+
+    match conn_type {
+        ConnectionType::Local => local_socket(),
+        ConnectionType::Ssh => match state.ssh_config.lock().unwrap().as_ref() {
+            Some(config) => host_address(&config.host),
+            None => String::new(),
+        },
+    }
+}
+
+// Hosts other than the active connection: `local`, `unix://` or `npipe://` sockets,
+// `tcp://host:port` or a bare `host[:port]`, which like SSH connections means the
+// daemon's HTTP port.
+fn docker_for_host(host: &str) -> Result<Docker, String> {
+    if host == "local" {
+        return Docker::connect_with_local_defaults()
+            .map_err(|e| format!("Failed to connect to local Docker: {}", e));
+    }
+
+    let address = host_address(host);
+    if address.starts_with("unix://") || address.starts_with("npipe://") {
+        return Docker::connect_with_local(&address, 120, bollard::API_DEFAULT_VERSION)
+            .map_err(|e| format!("Failed to connect to {}: {}", host, e));
+    }
+
+    Docker::connect_with_http(&address, 120, bollard::API_DEFAULT_VERSION)
+        .map_err(|e| format!("Failed to connect to {}: {}", host, e))
+}
+
 #[tauri::command]
 async fn connect_ssh(
     host: String,
@@ -336,10 +440,25 @@ async fn get_container_details(
         }
     }
 
+    let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.is_empty());
+
     let mut networks = Vec::new();
     if let Some(ref network_settings) = container.network_settings {
         if let Some(nets) = &network_settings.networks {
-            networks = nets.keys().cloned().collect();
+            for (name, endpoint) in nets {
+                networks.push(ContainerNetwork {
+                    name: name.clone(),
+                    network_id: endpoint.network_id.clone().unwrap_or_default(),
+                    ip_address: non_empty(&endpoint.ip_address),
+                    ip_prefix_len: endpoint.ip_prefix_len.filter(|len| *len > 0),
+                    gateway: non_empty(&endpoint.gateway),
+                    ipv6_address: non_empty(&endpoint.global_ipv6_address),
+                    ipv6_gateway: non_empty(&endpoint.ipv6_gateway),
+                    mac_address: non_empty(&endpoint.mac_address),
+                    aliases: endpoint.aliases.clone().unwrap_or_default(),
+                });
+            }
+            networks.sort_by(|a, b| a.name.cmp(&b.name));
         }
     }
 
@@ -526,64 +645,6 @@ async fn stop_container(
     Ok(())
 }
 
-#[tauri::command]
-async fn stream_container_logs(
-    id: String,
-    window: tauri::Window,
-    state: tauri::State<'_, DockerConnection>
-) -> Result<(), String> {
-    let docker = get_docker_connection(state)?;
-
-    let options = Some(LogsOptions {
-        follow: true, 
-        stdout: true,
-        stderr: true,
-        timestamps: true,
-        tail: "100".to_string(), 
-        ..Default::default()
-    });
-
-    let mut log_stream = docker.logs(&id, options); 
-
-    let (tx, rx) = oneshot::channel();
-    let tx = std::sync::Mutex::new(Some(tx));
-
-    let _unlisten = window.on_window_event(move |event| {
-        if let tauri::WindowEvent::Destroyed = event {
-            if let Some(tx) = tx.lock().unwrap().take() {
-                let _ = tx.send(());
-            }
-        }
-    });
-
-    let mut destroy_future = Box::pin(rx); 
-
-    while let Some(item) = tokio::select! {
-        item = log_stream.next() => item,
-        _ = &mut destroy_future => None,
-    } {
-        match item {
-            Ok(msg) => {
-                let log_text = match msg {
-                    LogOutput::StdOut { message } | LogOutput::StdErr { message } => {
-                        String::from_utf8_lossy(&message).trim().to_string()
-                    },
-                    _ => continue, 
-                };
-                
-                window.emit("log-update", log_text)
-                    .map_err(|e| format!("Failed to emit log: {}", e))?;
-            },
-            Err(e) => {
-                eprintln!("Log stream error: {}", e);
-                break;
-            }
-        }
-    }
-    
-    Ok(())
-}
-
 #[tauri::command]
 async fn remove_container(
     id: String,
@@ -619,6 +680,13 @@ async fn restart_container(
 fn main() {
     tauri::Builder::default()
         .manage(DockerConnection::default())
+        .manage(logs::LogSubscriptions::default())
+        .manage(operations::Operations::default())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                window.state::<logs::LogSubscriptions>().cancel_for_window(window.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             connect_ssh,
             connect_local,
@@ -628,12 +696,89 @@ fn main() {
             get_container_stats,
             get_images,
             get_networks,
+            networks::get_network_details,
+            networks::create_network,
+            networks::remove_network,
+            networks::prune_networks,
+            networks::connect_container_to_network,
+            networks::disconnect_container_from_network,
+            network_topology::get_network_topology,
+            volumes::get_volumes,
+            volumes::create_volume,
+            volumes::remove_volume,
+            volumes::prune_volumes,
+            volumes::get_volume_helper_image,
+            volumes::set_volume_helper_image,
+            volume_backup::backup_volume,
+            volume_backup::restore_volume,
+            file_browser::list_files,
+            file_browser::get_path_size,
+            file_browser::read_file,
+            file_browser::download_path,
+            file_browser::upload_path,
             start_container,
             stop_container,
             remove_container,
             restart_container,
-            stream_container_logs
+            container_recreate::recreate_container,
+            logs::stream_container_logs,
+            logs::stream_aggregated_logs,
+            logs::stop_log_stream,
+            logs::search_logs,
+            logs::export_logs,
+            images::pull_image,
+            images::push_image,
+            images::tag_image,
+            images::untag_image,
+            images::get_image_details,
+            image_layers::get_image_layer_contents,
+            image_updates::check_image_updates,
+            image_transfer::save_images,
+            image_transfer::load_images,
+            image_transfer::import_image,
+            image_transfer::transfer_image,
+            sbom::generate_sbom,
+            vulnerabilities::import_vulnerability_db,
+            vulnerabilities::get_vulnerability_db_info,
+            vulnerabilities::scan_image_vulnerabilities,
+            images::get_image_containers,
+            images::remove_image,
+            images::prune_images,
+            image_build::build_image,
+            credentials::registry_login,
+            credentials::registry_logout,
+            credentials::list_registry_logins,
+            registry_browser::list_registry_repositories,
+            registry_browser::list_registry_tags,
+            registry_browser::get_registry_manifest,
+            registry_browser::delete_registry_manifest,
+            operations::cancel_operation
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_host_addresses() {
+        assert_eq!(host_address("tcp://10.0.0.5:2376"), "http://10.0.0.5:2376");
+        assert_eq!(host_address("http://10.0.0.5"), "http://10.0.0.5:2375");
+        assert_eq!(host_address("http://10.0.0.5/"), "http://10.0.0.5:2375");
+        assert_eq!(host_address("tcp://Docker.Example.com"), "http://docker.example.com:2375");
+        assert_eq!(host_address("docker.example.com"), "http://docker.example.com:2375");
+        assert_eq!(host_address("docker.example.com:2375"), "http://docker.example.com:2375");
+        assert_eq!(host_address("[::1]"), "http://[::1]:2375");
+        assert_eq!(host_address("tcp://[::1]:2376"), "http://[::1]:2376");
+        assert_eq!(host_address("unix:///run/user/1000/docker.sock"), "unix:///run/user/1000/docker.sock");
+    }
+
+    #[test]
+    fn same_daemon_spellings_compare_equal() {
+        assert_eq!(host_address("tcp://10.0.0.5"), host_address("http://10.0.0.5:2375"));
+        assert_eq!(host_address("10.0.0.5"), host_address("tcp://10.0.0.5:2375/"));
+        assert_ne!(host_address("10.0.0.5"), host_address("10.0.0.5:2376"));
+    }
+}
@@ -0,0 +1,202 @@
+use bollard::models::ContainerInspectResponse;
+use bollard::query_parameters::{InspectContainerOptions, ListContainersOptionsBuilder, ListNetworksOptionsBuilder};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologyNode {
+    pub id: String,
+    // "network", "container" or "port"
+    pub kind: String,
+    pub label: String,
+    pub state: Option<String>,
+    pub driver: Option<String>,
+    pub internal: bool,
+    // Containers attached to several networks bridge traffic between them
+    pub multi_network: bool,
+    // Ports published on every host interface (0.0.0.0 / ::)
+    pub exposed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologyEdge {
+    pub source: String,
+    pub target: String,
+    // "network", "port", "link" or "shared-namespace"
+    pub kind: String,
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkTopology {
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+fn node(id: String, kind: &str, label: String) -> TopologyNode {
+    TopologyNode {
+        id,
+        kind: kind.to_string(),
+        label,
+        state: None,
+        driver: None,
+        internal: false,
+        multi_network: false,
+        exposed: false,
+    }
+}
+
+fn edge(source: &str, target: &str, kind: &str, label: Option<String>) -> TopologyEdge {
+    TopologyEdge {
+        source: source.to_string(),
+        target: target.to_string(),
+        kind: kind.to_string(),
+        label,
+    }
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+fn container_name(container: &ContainerInspectResponse) -> String {
+    container.name.as_deref()
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| short_id(container.id.as_deref().unwrap_or_default()).to_string())
+}
+
+fn is_all_interfaces(host_ip: &str) -> bool {
+    matches!(host_ip, "" | "0.0.0.0" | "::" | "[::]")
+}
+
+#[tauri::command]
+pub async fn get_network_topology(
+    all: Option<bool>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<NetworkTopology, String> {
+    let docker = get_docker_connection(state)?;
+
+    let networks = docker.list_networks(Some(ListNetworksOptionsBuilder::default().build()))
+        .await
+        .map_err(|e| format!("Failed to list networks: {}", e))?;
+
+    let options = ListContainersOptionsBuilder::default()
+        .all(all.unwrap_or(false))
+        .build();
+    let summaries = docker.list_containers(Some(options))
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    // Links and port bindings are only in the inspect data. Containers removed meanwhile are skipped.
+    let containers: Vec<ContainerInspectResponse> = join_all(summaries.iter()
+        .filter_map(|c| c.id.as_deref())
+        .map(|id| docker.inspect_container(id, None::<InspectContainerOptions>)))
+        .await
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect();
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+
+    let mut network_ids: HashMap<String, String> = HashMap::new();
+    for network in &networks {
+        let name = network.name.clone().unwrap_or_default();
+        let id = format!("network:{}", short_id(network.id.as_deref().unwrap_or(&name)));
+        network_ids.insert(name.clone(), id.clone());
+
+        let mut network_node = node(id, "network", name);
+        network_node.driver = network.driver.clone();
+        network_node.internal = network.internal.unwrap_or(false);
+        nodes.push(network_node);
+    }
+
+    let container_ids: HashMap<String, String> = containers.iter()
+        .map(|c| (container_name(c), format!("container:{}", short_id(c.id.as_deref().unwrap_or_default()))))
+        .collect();
+
+    for container in &containers {
+        let name = container_name(container);
+        let short = short_id(container.id.as_deref().unwrap_or_default());
+        let id = format!("container:{}", short);
+
+        let attached = container.network_settings.as_ref()
+            .and_then(|ns| ns.networks.clone())
+            .unwrap_or_default();
+
+        let mut container_node = node(id.clone(), "container", name);
+        container_node.state = container.state.as_ref()
+            .and_then(|s| s.status.as_ref())
+            .map(|s| s.to_string());
+        container_node.multi_network = attached.len() > 1;
+        nodes.push(container_node);
+
+        let mut attached: Vec<_> = attached.into_iter().collect();
+        attached.sort_by(|a, b| a.0.cmp(&b.0));
+        for (network, endpoint) in attached {
+            if let Some(network_id) = network_ids.get(&network) {
+                let ip = endpoint.ip_address.filter(|ip| !ip.is_empty());
+                edges.push(edge(&id, network_id, "network", ip));
+            }
+        }
+
+        // `--network container:<name|id>` shares the other container's network stack.
+        let network_mode = container.host_config.as_ref()
+            .and_then(|hc| hc.network_mode.clone())
+            .unwrap_or_default();
+        if let Some(target) = network_mode.strip_prefix("container:") {
+            let target_id = container_ids.get(target).cloned().or_else(|| containers.iter()
+                .find(|c| c.id.as_deref().is_some_and(|cid| cid.starts_with(target)))
+                .map(|c| format!("container:{}", short_id(c.id.as_deref().unwrap_or_default()))));
+            if let Some(target_id) = target_id {
+                edges.push(edge(&id, &target_id, "shared-namespace", None));
+            }
+        }
+
+        // Inspect reports legacy links as `/db:/web/db`.
+        for link in container.host_config.as_ref().and_then(|hc| hc.links.clone()).unwrap_or_default() {
+            let Some((target, alias)) = link.split_once(':') else {
+                continue;
+            };
+            if let Some(target_id) = container_ids.get(target.trim_start_matches('/')) {
+                let alias = alias.rsplit('/').next().unwrap_or(alias).to_string();
+                edges.push(edge(&id, target_id, "link", Some(alias)));
+            }
+        }
+
+        let mut bindings: Vec<_> = container.network_settings.as_ref()
+            .and_then(|ns| ns.ports.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (container_port, host_bindings) in bindings {
+            for binding in host_bindings.unwrap_or_default() {
+                let host_ip = binding.host_ip.unwrap_or_default();
+                let Some(host_port) = binding.host_port.filter(|p| !p.is_empty()) else {
+                    continue;
+                };
+
+                let label = if host_ip.contains(':') {
+                    format!("[{}]:{}", host_ip, host_port)
+                } else if host_ip.is_empty() {
+                    format!("0.0.0.0:{}", host_port)
+                } else {
+                    format!("{}:{}", host_ip, host_port)
+                };
+                let port_id = format!("port:{}:{}->{}", short, label, container_port);
+
+                let mut port_node = node(port_id.clone(), "port", label);
+                port_node.exposed = is_all_interfaces(&host_ip);
+                nodes.push(port_node);
+                edges.push(edge(&port_id, &id, "port", Some(container_port.clone())));
+            }
+        }
+    }
+
+    Ok(NetworkTopology { nodes, edges })
+}
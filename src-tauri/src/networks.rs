@@ -0,0 +1,292 @@
+use bollard::models::{
+    EndpointIpamConfig,
+    EndpointSettings,
+    Ipam,
+    IpamConfig,
+    NetworkConnectRequest,
+    NetworkCreateRequest,
+    NetworkDisconnectRequest,
+};
+use bollard::query_parameters::{InspectNetworkOptions, PruneNetworksOptionsBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkIpamConfig {
+    pub subnet: Option<String>,
+    pub gateway: Option<String>,
+    pub ip_range: Option<String>,
+    pub auxiliary_addresses: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkContainer {
+    pub id: String,
+    pub name: String,
+    pub ipv4_address: Option<String>,
+    pub ipv6_address: Option<String>,
+    pub mac_address: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkDetails {
+    pub id: String,
+    pub name: String,
+    pub driver: String,
+    pub scope: String,
+    pub created: Option<String>,
+    pub internal: bool,
+    pub attachable: bool,
+    pub ingress: bool,
+    pub enable_ipv6: bool,
+    pub ipam_driver: String,
+    pub ipam: Vec<NetworkIpamConfig>,
+    pub options: HashMap<String, String>,
+    pub labels: HashMap<String, String>,
+    pub containers: Vec<NetworkContainer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PruneNetworksResult {
+    pub deleted: Vec<String>,
+}
+
+// Inspect reports addresses in CIDR notation (`172.18.0.2/16`), empty when unassigned.
+fn address(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_network(
+    name: String,
+    driver: Option<String>,
+    subnet: Option<String>,
+    gateway: Option<String>,
+    ip_range: Option<String>,
+    enable_ipv6: Option<bool>,
+    internal: Option<bool>,
+    attachable: Option<bool>,
+    labels: Option<HashMap<String, String>>,
+    options: Option<HashMap<String, String>>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<String, String> {
+    let docker = get_docker_connection(state)?;
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Network name is required".to_string());
+    }
+
+    let non_empty = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let subnet = non_empty(subnet);
+    let gateway = non_empty(gateway);
+    let ip_range = non_empty(ip_range);
+
+    if subnet.is_none() && (gateway.is_some() || ip_range.is_some()) {
+        return Err("A gateway or IP range requires a subnet".to_string());
+    }
+
+    // Without a subnet the IPAM driver picks one from the default address pools.
+    let ipam = subnet.map(|subnet| Ipam {
+        driver: Some("default".to_string()),
+        config: Some(vec![IpamConfig {
+            subnet: Some(subnet),
+            gateway,
+            ip_range,
+            auxiliary_addresses: None,
+        }]),
+        options: None,
+    });
+
+    let request = NetworkCreateRequest {
+        name,
+        driver: Some(non_empty(driver).unwrap_or_else(|| "bridge".to_string())),
+        internal,
+        attachable,
+        ipam,
+        enable_ipv6,
+        options,
+        labels,
+        ..Default::default()
+    };
+
+    let response = docker.create_network(request)
+        .await
+        .map_err(|e| format!("Failed to create network: {}", e))?;
+
+    Ok(response.id)
+}
+
+#[tauri::command]
+pub async fn remove_network(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    let network = docker.inspect_network(&id, None::<InspectNetworkOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect network: {}", e))?;
+
+    // The Engine only reports "active endpoints", name the containers instead.
+    let attached: Vec<String> = network.containers.unwrap_or_default()
+        .into_iter()
+        .map(|(id, c)| c.name.unwrap_or_else(|| id.chars().take(12).collect()))
+        .collect();
+    if !attached.is_empty() {
+        return Err(format!("Network is in use by {}", attached.join(", ")));
+    }
+
+    docker.remove_network(&id)
+        .await
+        .map_err(|e| format!("Failed to remove network: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn prune_networks(
+    until: Option<String>,
+    labels: Option<Vec<String>>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<PruneNetworksResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let mut filters: HashMap<&str, Vec<String>> = HashMap::new();
+
+    if let Some(until) = until {
+        filters.insert("until", vec![until]);
+    }
+
+    // `!key=value` excludes networks with that label.
+    for label in labels.unwrap_or_default() {
+        match label.strip_prefix('!') {
+            Some(label) => filters.entry("label!").or_default().push(label.to_string()),
+            None => filters.entry("label").or_default().push(label),
+        }
+    }
+
+    let options = PruneNetworksOptionsBuilder::default()
+        .filters(&filters)
+        .build();
+
+    let response = docker.prune_networks(Some(options))
+        .await
+        .map_err(|e| format!("Failed to prune networks: {}", e))?;
+
+    Ok(PruneNetworksResult {
+        deleted: response.networks_deleted.unwrap_or_default(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_network_details(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<NetworkDetails, String> {
+    let docker = get_docker_connection(state)?;
+
+    let network = docker.inspect_network(&id, None::<InspectNetworkOptions>)
+        .await
+        .map_err(|e| format!("Failed to inspect network: {}", e))?;
+
+    let ipam = network.ipam.unwrap_or_default();
+
+    let mut containers: Vec<NetworkContainer> = network.containers.unwrap_or_default()
+        .into_iter()
+        .map(|(id, c)| NetworkContainer {
+            name: c.name.unwrap_or_else(|| id.chars().take(12).collect()),
+            id: id.chars().take(12).collect(),
+            ipv4_address: address(c.ipv4_address),
+            ipv6_address: address(c.ipv6_address),
+            mac_address: address(c.mac_address),
+        })
+        .collect();
+    containers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(NetworkDetails {
+        id: network.id.unwrap_or_default(),
+        name: network.name.unwrap_or_default(),
+        driver: network.driver.unwrap_or_default(),
+        scope: network.scope.unwrap_or_else(|| "local".to_string()),
+        created: network.created,
+        internal: network.internal.unwrap_or(false),
+        attachable: network.attachable.unwrap_or(false),
+        ingress: network.ingress.unwrap_or(false),
+        enable_ipv6: network.enable_ipv6.unwrap_or(false),
+        ipam_driver: ipam.driver.unwrap_or_else(|| "default".to_string()),
+        ipam: ipam.config.unwrap_or_default()
+            .into_iter()
+            .map(|c| NetworkIpamConfig {
+                subnet: c.subnet,
+                gateway: c.gateway,
+                ip_range: c.ip_range,
+                auxiliary_addresses: c.auxiliary_addresses.unwrap_or_default(),
+            })
+            .collect(),
+        options: network.options.unwrap_or_default(),
+        labels: network.labels.unwrap_or_default(),
+        containers,
+    })
+}
+
+#[tauri::command]
+pub async fn connect_container_to_network(
+    container: String,
+    network: String,
+    aliases: Option<Vec<String>>,
+    ipv4: Option<String>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    let aliases: Vec<String> = aliases.unwrap_or_default()
+        .into_iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    let ipv4 = ipv4.map(|ip| ip.trim().to_string()).filter(|ip| !ip.is_empty());
+
+    // A static address only works on networks with a user-defined subnet.
+    let request = NetworkConnectRequest {
+        container: Some(container),
+        endpoint_config: Some(EndpointSettings {
+            aliases: (!aliases.is_empty()).then_some(aliases),
+            ipam_config: ipv4.map(|ip| EndpointIpamConfig {
+                ipv4_address: Some(ip),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    };
+
+    docker.connect_network(&network, request)
+        .await
+        .map_err(|e| format!("Failed to connect to network: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn disconnect_container_from_network(
+    container: String,
+    network: String,
+    force: Option<bool>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    let request = NetworkDisconnectRequest {
+        container: Some(container),
+        force: Some(force.unwrap_or(false)),
+    };
+
+    docker.disconnect_network(&network, request)
+        .await
+        .map_err(|e| format!("Failed to disconnect from network: {}", e))?;
+
+    Ok(())
+}
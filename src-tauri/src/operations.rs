@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+// Long running Docker calls (pulls, pushes, builds...) register here under an id chosen
+// by the frontend, so they can be cancelled while the command is still awaiting.
+#[derive(Default)]
+pub struct Operations {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+pub struct OperationGuard<'a> {
+    operations: &'a Operations,
+    pub id: String,
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        self.operations.active.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl Operations {
+    pub fn register(&self, id: Option<String>) -> Result<(OperationGuard<'_>, oneshot::Receiver<()>), String> {
+        let id = id.unwrap_or_else(|| format!("op-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1));
+        let (cancel, cancel_rx) = oneshot::channel();
+
+        let mut active = self.active.lock().unwrap();
+        if active.contains_key(&id) {
+            return Err(format!("Operation {} is already running", id));
+        }
+        active.insert(id.clone(), cancel);
+
+        Ok((OperationGuard { operations: self, id }, cancel_rx))
+    }
+
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.active.lock().unwrap().remove(id) {
+            Some(cancel) => {
+                let _ = cancel.send(());
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_operation(
+    id: String,
+    operations: tauri::State<'_, Operations>
+) -> Result<(), String> {
+    if operations.cancel(&id) {
+        Ok(())
+    } else {
+        Err(format!("Operation {} not found", id))
+    }
+}
@@ -0,0 +1,988 @@
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+
+use crate::image_archive::ImageArchive;
+
+// Text databases and lockfiles are small; binaries are only read to find Go build info.
+const MAX_TEXT_SIZE: u64 = 64 * 1024 * 1024;
+const MAX_BINARY_SIZE: u64 = 256 * 1024 * 1024;
+
+const GO_BUILDINFO_MAGIC: &[u8] = b"\xff Go buildinf:";
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+const RPM_TAG_NAME: u32 = 1000;
+const RPM_TAG_VERSION: u32 = 1001;
+const RPM_TAG_RELEASE: u32 = 1002;
+const RPM_TAG_EPOCH: u32 = 1003;
+const RPM_TAG_LICENSE: u32 = 1014;
+const RPM_TAG_ARCH: u32 = 1022;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImagePackage {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: String,
+    pub purl: String,
+    pub license: Option<String>,
+    pub source: Option<String>,
+    pub locations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OsRelease {
+    pub id: String,
+    pub version_id: String,
+    pub pretty_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImagePackages {
+    pub image_id: String,
+    pub name: String,
+    pub os: Option<OsRelease>,
+    pub packages: Vec<ImagePackage>,
+}
+
+enum Captured {
+    Text(Vec<u8>),
+    Go(Vec<ImagePackage>),
+}
+
+fn is_package_database(path: &str) -> bool {
+    let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+
+    matches!(
+        path,
+        "var/lib/dpkg/status"
+            | "lib/apk/db/installed"
+            | "var/lib/rpm/rpmdb.sqlite"
+            | "usr/lib/sysimage/rpm/rpmdb.sqlite"
+            | "etc/os-release"
+            | "usr/lib/os-release"
+    ) || (dir == "var/lib/dpkg/status.d" && !name.ends_with(".md5sums"))
+        || matches!(name, "package-lock.json" | "Cargo.lock" | "requirements.txt")
+        || (name == "METADATA" && dir.ends_with(".dist-info"))
+        || (name == "PKG-INFO" && dir.ends_with(".egg-info"))
+        || (name.ends_with(".egg-info") && (dir.ends_with("site-packages") || dir.ends_with("dist-packages")))
+}
+
+fn remove_below(files: &mut BTreeMap<String, Captured>, prefix: &str) {
+    let paths: Vec<String> = files.range(prefix.to_string()..)
+        .take_while(|(path, _)| path.starts_with(prefix))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    for path in paths {
+        files.remove(&path);
+    }
+}
+
+// Replays the layers so only the files present in the final image are cataloged.
+fn capture_files(archive: &ImageArchive) -> Result<BTreeMap<String, Captured>, String> {
+    let mut files: BTreeMap<String, Captured> = BTreeMap::new();
+
+    for index in 0..archive.manifest.layers.len() {
+        let mut layer = archive.open_layer(index)?;
+
+        for entry in layer.entries().map_err(|e| format!("Failed to read layer {}: {}", index, e))? {
+            let mut entry = entry.map_err(|e| format!("Failed to read layer {}: {}", index, e))?;
+
+            let path = entry.path()
+                .map_err(|e| format!("Failed to read layer {}: {}", index, e))?
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .trim_matches('/')
+                .to_string();
+            let (dir, name) = path.rsplit_once('/').unwrap_or(("", &path));
+
+            if name == ".wh..wh..opq" {
+                let prefix = if dir.is_empty() { String::new() } else { format!("{}/", dir) };
+                remove_below(&mut files, &prefix);
+                continue;
+            }
+            if let Some(target) = name.strip_prefix(".wh.") {
+                let target = if dir.is_empty() { target.to_string() } else { format!("{}/{}", dir, target) };
+                files.remove(&target);
+                remove_below(&mut files, &format!("{}/", target));
+                continue;
+            }
+
+            // A later layer replacing a file with anything else hides the old content.
+            files.remove(&path);
+
+            let header = entry.header();
+            if !header.entry_type().is_file() {
+                continue;
+            }
+
+            let size = entry.size();
+            let executable = header.mode().map(|mode| mode & 0o111 != 0).unwrap_or(false);
+
+            if is_package_database(&path) {
+                if size > MAX_TEXT_SIZE {
+                    continue;
+                }
+                let mut content = Vec::with_capacity(size as usize);
+                entry.read_to_end(&mut content)
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                files.insert(path, Captured::Text(content));
+            } else if executable && size > ELF_MAGIC.len() as u64 && size <= MAX_BINARY_SIZE {
+                let mut magic = [0u8; 4];
+                if entry.read_exact(&mut magic).is_err() || magic != ELF_MAGIC {
+                    continue;
+                }
+
+                let mut content = magic.to_vec();
+                entry.read_to_end(&mut content)
+                    .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+                let location = format!("/{}", path);
+                if let Some(packages) = go_build_info(&content, &location) {
+                    files.insert(path, Captured::Go(packages));
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn purl_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+// `pkg:type/namespace/name@version?key=value`, see github.com/package-url/purl-spec.
+fn purl(kind: &str, namespace: Option<&str>, name: &str, version: &str, qualifiers: &[(&str, &str)]) -> String {
+    let mut purl = format!("pkg:{}/", kind);
+    if let Some(namespace) = namespace.filter(|n| !n.is_empty()) {
+        purl.push_str(&purl_encode(namespace));
+        purl.push('/');
+    }
+    purl.push_str(&purl_encode(name));
+
+    if !version.is_empty() {
+        purl.push('@');
+        purl.push_str(&purl_encode(version).replace('/', "%2F"));
+    }
+
+    let qualifiers: Vec<String> = qualifiers.iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!("{}={}", key, purl_encode(value).replace('/', "%2F")))
+        .collect();
+    if !qualifiers.is_empty() {
+        purl.push('?');
+        purl.push_str(&qualifiers.join("&"));
+    }
+
+    purl
+}
+
+fn package(ecosystem: &str, name: &str, version: &str, purl: String, license: Option<String>, location: &str) -> ImagePackage {
+    ImagePackage {
+        name: name.to_string(),
+        version: version.to_string(),
+        ecosystem: ecosystem.to_string(),
+        purl,
+        license: license.filter(|l| !l.is_empty()),
+        source: None,
+        locations: vec![location.to_string()],
+    }
+}
+
+fn parse_os_release(content: &str) -> OsRelease {
+    let mut values = HashMap::new();
+    for line in content.lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            values.insert(key.trim(), value.trim().trim_matches('"').trim_matches('\'').to_string());
+        }
+    }
+
+    OsRelease {
+        id: values.get("ID").cloned().unwrap_or_default(),
+        version_id: values.get("VERSION_ID").cloned().unwrap_or_default(),
+        pretty_name: values.get("PRETTY_NAME").cloned().unwrap_or_default(),
+    }
+}
+
+// Debian control files and Python metadata: `Key: value` with indented continuations,
+// paragraphs separated by blank lines.
+fn parse_paragraphs(content: &str) -> Vec<HashMap<String, String>> {
+    let mut paragraphs = Vec::new();
+    let mut current: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            last_key = None;
+        } else if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some(value) = last_key.as_ref().and_then(|key| current.get_mut(key)) {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_string();
+            current.entry(key.clone()).or_insert_with(|| value.trim().to_string());
+            last_key = Some(key);
+        }
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+
+    paragraphs
+}
+
+fn parse_dpkg(content: &str, distro: &str, location: &str) -> Vec<ImagePackage> {
+    parse_paragraphs(content).into_iter()
+        .filter(|p| p.get("Status").is_none_or(|status| status.ends_with(" installed")))
+        .filter_map(|p| {
+            let name = p.get("Package")?;
+            let version = p.get("Version")?;
+            let arch = p.get("Architecture").map(String::as_str).unwrap_or_default();
+            let source = p.get("Source")
+                .map(|s| s.split_whitespace().next().unwrap_or_default())
+                .unwrap_or_default();
+
+            let purl = purl("deb", Some(distro), name, version, &[("arch", arch), ("upstream", source)]);
+            let mut found = package("deb", name, version, purl, None, location);
+            found.source = Some(source).filter(|s| !s.is_empty()).map(String::from);
+            Some(found)
+        })
+        .collect()
+}
+
+// apk's installed db uses single letter keys: `P:name`, `V:version`, `A:arch`, `L:license`.
+fn parse_apk(content: &str, distro: &str, location: &str) -> Vec<ImagePackage> {
+    content.split("\n\n")
+        .filter_map(|block| {
+            let mut fields: HashMap<&str, &str> = HashMap::new();
+            for line in block.lines() {
+                if let Some((key, value)) = line.split_once(':') {
+                    fields.entry(key).or_insert(value);
+                }
+            }
+
+            let name = fields.get("P")?;
+            let version = fields.get("V")?;
+            let arch = fields.get("A").copied().unwrap_or_default();
+            let origin = fields.get("o").copied().unwrap_or_default();
+
+            let purl = purl("apk", Some(distro), name, version, &[("arch", arch), ("upstream", origin)]);
+            let mut found = package("apk", name, version, purl, fields.get("L").map(|l| l.to_string()), location);
+            found.source = Some(origin).filter(|o| !o.is_empty()).map(String::from);
+            Some(found)
+        })
+        .collect()
+}
+
+// RPM header blobs: index entry count and data size, 16 byte index entries
+// (tag, type, offset, count), then the data store.
+fn parse_rpm_header(blob: &[u8]) -> HashMap<u32, String> {
+    let mut values = HashMap::new();
+
+    let read_u32 = |at: usize| blob.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+    let (Some(index_count), Some(data_size)) = (read_u32(0), read_u32(4)) else {
+        return values;
+    };
+    let data_start = 8 + index_count as usize * 16;
+    let Some(data) = blob.get(data_start..data_start + data_size as usize) else {
+        return values;
+    };
+
+    for i in 0..index_count as usize {
+        let at = 8 + i * 16;
+        let (Some(tag), Some(kind), Some(offset)) = (read_u32(at), read_u32(at + 4), read_u32(at + 8)) else {
+            break;
+        };
+        let Some(value) = data.get(offset as usize..) else {
+            continue;
+        };
+
+        let value = match kind {
+            // INT32
+            4 => value.get(..4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]).to_string()),
+            // STRING, STRING_ARRAY (first element), I18NSTRING
+            6 | 8 | 9 => {
+                let end = value.iter().position(|&b| b == 0).unwrap_or(value.len());
+                Some(String::from_utf8_lossy(&value[..end]).to_string())
+            },
+            _ => None,
+        };
+
+        if let Some(value) = value {
+            values.entry(tag).or_insert(value);
+        }
+    }
+
+    values
+}
+
+// Only the sqlite rpmdb (RHEL 9, Fedora 33+, recent SUSE) is read. The BerkeleyDB and
+// NDB formats of older releases would need their own parsers.
+fn parse_rpmdb(content: &[u8], distro: &str, location: &str) -> Result<Vec<ImagePackage>, String> {
+    let mut file = tempfile::NamedTempFile::new()
+        .map_err(|e| format!("Failed to create temporary file: {}", e))?;
+    file.write_all(content)
+        .map_err(|e| format!("Failed to write rpm database: {}", e))?;
+
+    let connection = rusqlite::Connection::open_with_flags(file.path(), rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open rpm database: {}", e))?;
+    let mut statement = connection.prepare("SELECT blob FROM Packages")
+        .map_err(|e| format!("Failed to read rpm database: {}", e))?;
+    let blobs = statement.query_map([], |row| row.get::<_, Vec<u8>>(0))
+        .map_err(|e| format!("Failed to read rpm database: {}", e))?;
+
+    let mut packages = Vec::new();
+    for blob in blobs {
+        let blob = blob.map_err(|e| format!("Failed to read rpm database: {}", e))?;
+        let header = parse_rpm_header(&blob);
+
+        let (Some(name), Some(version)) = (header.get(&RPM_TAG_NAME), header.get(&RPM_TAG_VERSION)) else {
+            continue;
+        };
+        // Imported signing keys show up as packages.
+        if name == "gpg-pubkey" {
+            continue;
+        }
+
+        let release = header.get(&RPM_TAG_RELEASE).map(String::as_str).unwrap_or_default();
+        let epoch = header.get(&RPM_TAG_EPOCH).map(String::as_str).unwrap_or_default();
+        let arch = header.get(&RPM_TAG_ARCH).map(String::as_str).unwrap_or_default();
+
+        let version = if release.is_empty() { version.clone() } else { format!("{}-{}", version, release) };
+        let full_version = if epoch.is_empty() { version.clone() } else { format!("{}:{}", epoch, version) };
+
+        let purl = purl("rpm", Some(distro), name, &version, &[("arch", arch), ("epoch", epoch)]);
+        packages.push(package("rpm", name, &full_version, purl, header.get(&RPM_TAG_LICENSE).cloned(), location));
+    }
+
+    Ok(packages)
+}
+
+fn npm_package(name: &str, version: &str, license: Option<String>, location: &str) -> ImagePackage {
+    let (namespace, short) = match name.strip_prefix('@').and_then(|n| n.split_once('/')) {
+        Some((scope, short)) => (Some(format!("@{}", scope)), short),
+        None => (None, name),
+    };
+    let purl = purl("npm", namespace.as_deref(), short, version, &[]);
+    package("npm", name, version, purl, license, location)
+}
+
+fn collect_npm_v1(dependencies: &serde_json::Map<String, serde_json::Value>, location: &str, packages: &mut Vec<ImagePackage>) {
+    for (name, dependency) in dependencies {
+        if let Some(version) = dependency["version"].as_str() {
+            packages.push(npm_package(name, version, None, location));
+        }
+        if let Some(nested) = dependency["dependencies"].as_object() {
+            collect_npm_v1(nested, location, packages);
+        }
+    }
+}
+
+// Lockfile v2/v3 list installs under `packages` keyed by their node_modules path,
+// v1 nests them under `dependencies`.
+fn parse_package_lock(content: &[u8], location: &str) -> Vec<ImagePackage> {
+    let Ok(lock) = serde_json::from_slice::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    let mut packages = Vec::new();
+
+    if let Some(installed) = lock["packages"].as_object() {
+        for (path, entry) in installed {
+            if path.is_empty() || entry["link"].as_bool().unwrap_or(false) {
+                continue;
+            }
+            let name = entry["name"].as_str()
+                .unwrap_or_else(|| path.rsplit("node_modules/").next().unwrap_or(path));
+            let Some(version) = entry["version"].as_str() else {
+                continue;
+            };
+            let license = entry["license"].as_str().map(String::from);
+            packages.push(npm_package(name, version, license, location));
+        }
+    } else if let Some(dependencies) = lock["dependencies"].as_object() {
+        collect_npm_v1(dependencies, location, &mut packages);
+    }
+
+    packages
+}
+
+#[derive(Deserialize)]
+struct CargoLock {
+    #[serde(default)]
+    package: Vec<CargoLockPackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoLockPackage {
+    name: String,
+    version: String,
+}
+
+fn parse_cargo_lock(content: &[u8], location: &str) -> Vec<ImagePackage> {
+    let Ok(lock) = toml::from_str::<CargoLock>(&String::from_utf8_lossy(content)) else {
+        return Vec::new();
+    };
+
+    lock.package.into_iter()
+        .map(|p| {
+            let purl = purl("cargo", None, &p.name, &p.version, &[]);
+            package("cargo", &p.name, &p.version, purl, None, location)
+        })
+        .collect()
+}
+
+// PyPI names compare case-insensitively with `-`, `_` and `.` equivalent.
+fn pypi_name(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+fn python_package(name: &str, version: &str, license: Option<String>, location: &str) -> ImagePackage {
+    let purl = purl("pypi", None, &pypi_name(name), version, &[]);
+    package("pypi", name, version, purl, license, location)
+}
+
+// Only pinned requirements (`name==1.2.3`) carry a version, the rest are listed without one.
+fn parse_requirements(content: &str, location: &str) -> Vec<ImagePackage> {
+    content.lines()
+        .filter_map(|line| {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('-') || line.contains("://") {
+                return None;
+            }
+
+            let line = line.split(';').next().unwrap_or_default().trim();
+            let end = line.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))).unwrap_or(line.len());
+            let name = &line[..end];
+            if name.is_empty() {
+                return None;
+            }
+
+            let rest = line[end..].trim_start();
+            let rest = match rest.strip_prefix('[') {
+                Some(extras) => extras.split_once(']').map(|(_, r)| r.trim_start()).unwrap_or_default(),
+                None => rest,
+            };
+            let version = rest.strip_prefix("===")
+                .or_else(|| rest.strip_prefix("=="))
+                .map(|v| v.split(',').next().unwrap_or_default().trim())
+                .unwrap_or_default();
+
+            Some(python_package(name, version, None, location))
+        })
+        .collect()
+}
+
+fn parse_python_metadata(content: &str, location: &str) -> Option<ImagePackage> {
+    let headers = parse_paragraphs(content).into_iter().next()?;
+    let name = headers.get("Name")?;
+    let version = headers.get("Version")?;
+    let license = headers.get("License-Expression")
+        .or(headers.get("License"))
+        .filter(|l| !l.is_empty() && l.as_str() != "UNKNOWN" && !l.contains('\n'))
+        .cloned();
+
+    Some(python_package(name, version, license, location))
+}
+
+fn read_uvarint(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+// Go 1.18+ binaries embed `\xff Go buildinf:` followed by the toolchain version and the
+// module list as varint prefixed strings. Older binaries store pointers instead and are
+// skipped.
+fn go_build_info(binary: &[u8], location: &str) -> Option<Vec<ImagePackage>> {
+    for start in memchr::memmem::find_iter(binary, GO_BUILDINFO_MAGIC) {
+        let Some(header) = binary.get(start..start + 32) else {
+            continue;
+        };
+        if header[15] & 0x2 == 0 {
+            continue;
+        }
+
+        let rest = &binary[start + 32..];
+        let Some((length, used)) = read_uvarint(rest) else {
+            continue;
+        };
+        let Some(go_version) = rest.get(used..used + length) else {
+            continue;
+        };
+        let rest = &rest[used + length..];
+        let Some((length, used)) = read_uvarint(rest) else {
+            continue;
+        };
+        let Some(mut modinfo) = rest.get(used..used + length) else {
+            continue;
+        };
+
+        // The module list is wrapped in 16 byte sentinels.
+        if modinfo.len() >= 33 && modinfo[modinfo.len() - 17] == b'\n' {
+            modinfo = &modinfo[16..modinfo.len() - 16];
+        }
+
+        // `go1.23.1 X:nocoverageredesign` lists enabled experiments after the version.
+        let go_version = String::from_utf8_lossy(go_version);
+        let go_version = go_version.split_whitespace().next().unwrap_or_default().trim_start_matches("go");
+        let mut packages = vec![package(
+            "golang",
+            "stdlib",
+            go_version,
+            purl("golang", None, "stdlib", go_version, &[]),
+            Some("BSD-3-Clause".to_string()),
+            location,
+        )];
+
+        for line in String::from_utf8_lossy(modinfo).lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                ["mod" | "dep", path, version, ..] => {
+                    packages.push(package("golang", path, version, purl("golang", None, path, version, &[]), None, location));
+                },
+                // `=>` replaces the dependency listed just before it.
+                ["=>", path, version, ..] => {
+                    if let Some(last) = packages.last_mut() {
+                        *last = package("golang", path, version, purl("golang", None, path, version, &[]), None, location);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        return Some(packages);
+    }
+
+    None
+}
+
+fn parse_captured(files: BTreeMap<String, Captured>) -> Result<(Option<OsRelease>, Vec<ImagePackage>), String> {
+    let os = files.get("etc/os-release")
+        .or(files.get("usr/lib/os-release"))
+        .and_then(|captured| match captured {
+            Captured::Text(content) => Some(parse_os_release(&String::from_utf8_lossy(content))),
+            Captured::Go(_) => None,
+        });
+    let distro = os.as_ref().map(|os| os.id.clone()).unwrap_or_default();
+
+    let mut packages = Vec::new();
+
+    for (path, captured) in files {
+        let location = format!("/{}", path);
+        let content = match captured {
+            Captured::Go(found) => {
+                packages.extend(found);
+                continue;
+            },
+            Captured::Text(content) => content,
+        };
+        let name = path.rsplit('/').next().unwrap_or(&path);
+
+        if path == "var/lib/dpkg/status" || path.starts_with("var/lib/dpkg/status.d/") {
+            packages.extend(parse_dpkg(&String::from_utf8_lossy(&content), &distro, &location));
+        } else if path == "lib/apk/db/installed" {
+            packages.extend(parse_apk(&String::from_utf8_lossy(&content), &distro, &location));
+        } else if name == "rpmdb.sqlite" {
+            packages.extend(parse_rpmdb(&content, &distro, &location)?);
+        } else if name == "package-lock.json" {
+            packages.extend(parse_package_lock(&content, &location));
+        } else if name == "Cargo.lock" {
+            packages.extend(parse_cargo_lock(&content, &location));
+        } else if name == "requirements.txt" {
+            packages.extend(parse_requirements(&String::from_utf8_lossy(&content), &location));
+        } else if name == "METADATA" || name == "PKG-INFO" || name.ends_with(".egg-info") {
+            packages.extend(parse_python_metadata(&String::from_utf8_lossy(&content), &location));
+        }
+    }
+
+    // The same package is often found by several sources, e.g. a lockfile and metadata.
+    // The purl carries the ecosystem's name normalization and is the SBOM bom-ref, so it is the key.
+    let mut unique: BTreeMap<String, ImagePackage> = BTreeMap::new();
+    for found in packages {
+        let key = found.purl.clone();
+        match unique.get_mut(&key) {
+            Some(existing) => {
+                for location in found.locations {
+                    if !existing.locations.contains(&location) {
+                        existing.locations.push(location);
+                    }
+                }
+                if existing.license.is_none() {
+                    existing.license = found.license;
+                }
+            },
+            None => {
+                unique.insert(key, found);
+            },
+        }
+    }
+
+    Ok((os, unique.into_values().collect()))
+}
+
+pub async fn collect_image_packages(docker: &Docker, image: &str) -> Result<ImagePackages, String> {
+    let details = docker.inspect_image(image)
+        .await
+        .map_err(|e| format!("Failed to inspect image: {}", e))?;
+
+    let image_id = details.id.unwrap_or_else(|| image.to_string());
+    let name = details.repo_tags
+        .and_then(|tags| tags.into_iter().next())
+        .unwrap_or_else(|| image.to_string());
+
+    let archive = ImageArchive::export(docker, &image_id).await?;
+
+    let (os, packages) = tauri::async_runtime::spawn_blocking(move || parse_captured(capture_files(&archive)?))
+        .await
+        .map_err(|e| format!("Failed to catalog image packages: {}", e))??;
+
+    Ok(ImagePackages {
+        image_id,
+        name,
+        os,
+        packages,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(packages: &[ImagePackage]) -> Vec<(&str, &str)> {
+        packages.iter().map(|p| (p.name.as_str(), p.version.as_str())).collect()
+    }
+
+    // Header blob with (tag, type, value) entries, see parse_rpm_header.
+    fn rpm_header(entries: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let mut index = Vec::new();
+        let mut data = Vec::new();
+        for (tag, kind, value) in entries {
+            index.extend_from_slice(&tag.to_be_bytes());
+            index.extend_from_slice(&kind.to_be_bytes());
+            index.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            index.extend_from_slice(&1u32.to_be_bytes());
+            data.extend_from_slice(value);
+        }
+
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        blob.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        blob.extend(index);
+        blob.extend(data);
+        blob
+    }
+
+    #[test]
+    fn parses_dpkg_status() {
+        let status = "\
+Package: libc6
+Status: install ok installed
+Architecture: amd64
+Source: glibc (2.36-9+deb12u4)
+Version: 2.36-9+deb12u4
+Description: GNU C Library: Shared libraries
+ Contains the standard libraries that are used by nearly all programs on
+ the system.
+
+Package: removed
+Status: deinstall ok config-files
+Version: 1.0-1
+
+Package: bsdutils
+Status: install ok installed
+Architecture: amd64
+Version: 1:2.38.1-5+b1
+";
+        let packages = parse_dpkg(status, "debian", "/var/lib/dpkg/status");
+
+        assert_eq!(names(&packages), [("libc6", "2.36-9+deb12u4"), ("bsdutils", "1:2.38.1-5+b1")]);
+        assert_eq!(packages[0].source.as_deref(), Some("glibc"));
+        assert_eq!(packages[0].purl, "pkg:deb/debian/libc6@2.36-9%2Bdeb12u4?arch=amd64&upstream=glibc");
+        assert_eq!(packages[1].purl, "pkg:deb/debian/bsdutils@1%3A2.38.1-5%2Bb1?arch=amd64");
+    }
+
+    #[test]
+    fn joins_continuation_lines() {
+        let paragraphs = parse_paragraphs("A: one\n two\n\tthree\nB: x\n\n\nA: second\n");
+
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0]["A"], "one\ntwo\nthree");
+        assert_eq!(paragraphs[0]["B"], "x");
+        assert_eq!(paragraphs[1]["A"], "second");
+    }
+
+    #[test]
+    fn parses_apk_installed() {
+        let installed = "\
+C:Q1abc=
+P:musl
+V:1.2.4-r2
+A:x86_64
+L:MIT
+o:musl
+
+C:Q1def=
+P:busybox-binsh
+V:1.36.1-r15
+A:x86_64
+L:GPL-2.0-only
+o:busybox
+";
+        let packages = parse_apk(installed, "alpine", "/lib/apk/db/installed");
+
+        assert_eq!(names(&packages), [("musl", "1.2.4-r2"), ("busybox-binsh", "1.36.1-r15")]);
+        assert_eq!(packages[1].license.as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(packages[1].source.as_deref(), Some("busybox"));
+        assert_eq!(packages[1].purl, "pkg:apk/alpine/busybox-binsh@1.36.1-r15?arch=x86_64&upstream=busybox");
+    }
+
+    #[test]
+    fn parses_rpm_headers() {
+        let header = rpm_header(&[
+            (RPM_TAG_NAME, 6, b"bash\0"),
+            (RPM_TAG_VERSION, 6, b"5.1.8\0"),
+            (RPM_TAG_RELEASE, 6, b"9.el9\0"),
+            (RPM_TAG_EPOCH, 4, &1u32.to_be_bytes()),
+            (RPM_TAG_LICENSE, 6, b"GPLv3+\0"),
+            (RPM_TAG_ARCH, 6, b"x86_64\0"),
+        ]);
+        let values = parse_rpm_header(&header);
+
+        assert_eq!(values[&RPM_TAG_NAME], "bash");
+        assert_eq!(values[&RPM_TAG_EPOCH], "1");
+        assert_eq!(values[&RPM_TAG_LICENSE], "GPLv3+");
+
+        // Truncated blobs yield nothing instead of reading out of bounds.
+        assert!(parse_rpm_header(&header[..20]).is_empty());
+        assert!(parse_rpm_header(&[]).is_empty());
+    }
+
+    #[test]
+    fn parses_rpm_sqlite_database() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let connection = rusqlite::Connection::open(file.path()).unwrap();
+        connection.execute("CREATE TABLE Packages (hnum INTEGER PRIMARY KEY, blob BLOB NOT NULL)", []).unwrap();
+
+        let packages = [
+            rpm_header(&[
+                (RPM_TAG_NAME, 6, b"openssl-libs\0"),
+                (RPM_TAG_VERSION, 6, b"3.0.7\0"),
+                (RPM_TAG_RELEASE, 6, b"27.el9\0"),
+                (RPM_TAG_EPOCH, 4, &1u32.to_be_bytes()),
+                (RPM_TAG_ARCH, 6, b"x86_64\0"),
+            ]),
+            rpm_header(&[
+                (RPM_TAG_NAME, 6, b"gpg-pubkey\0"),
+                (RPM_TAG_VERSION, 6, b"fd431d51\0"),
+            ]),
+            rpm_header(&[
+                (RPM_TAG_NAME, 6, b"tzdata\0"),
+                (RPM_TAG_VERSION, 6, b"2024a\0"),
+                (RPM_TAG_RELEASE, 6, b"1.el9\0"),
+                (RPM_TAG_ARCH, 6, b"noarch\0"),
+            ]),
+        ];
+        for blob in &packages {
+            connection.execute("INSERT INTO Packages (blob) VALUES (?1)", [blob]).unwrap();
+        }
+        drop(connection);
+
+        let content = std::fs::read(file.path()).unwrap();
+        let packages = parse_rpmdb(&content, "rocky", "/var/lib/rpm/rpmdb.sqlite").unwrap();
+
+        assert_eq!(names(&packages), [("openssl-libs", "1:3.0.7-27.el9"), ("tzdata", "2024a-1.el9")]);
+        assert_eq!(packages[0].purl, "pkg:rpm/rocky/openssl-libs@3.0.7-27.el9?arch=x86_64&epoch=1");
+        assert_eq!(packages[1].purl, "pkg:rpm/rocky/tzdata@2024a-1.el9?arch=noarch");
+    }
+
+    #[test]
+    fn parses_package_lock_v3() {
+        let lock = br#"{
+            "name": "app",
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "name": "app", "version": "1.0.0" },
+                "node_modules/@babel/core": { "version": "7.24.0", "license": "MIT" },
+                "node_modules/debug/node_modules/ms": { "version": "2.1.2" },
+                "node_modules/local-lib": { "resolved": "../lib", "link": true },
+                "node_modules/aliased": { "name": "real-name", "version": "3.0.0" }
+            }
+        }"#;
+        let packages = parse_package_lock(lock, "/app/package-lock.json");
+
+        assert_eq!(names(&packages), [("@babel/core", "7.24.0"), ("real-name", "3.0.0"), ("ms", "2.1.2")]);
+        assert_eq!(packages[0].purl, "pkg:npm/%40babel/core@7.24.0");
+        assert_eq!(packages[0].license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn parses_package_lock_v1() {
+        let lock = br#"{
+            "lockfileVersion": 1,
+            "dependencies": {
+                "debug": {
+                    "version": "4.3.4",
+                    "dependencies": { "ms": { "version": "2.1.2" } }
+                },
+                "ms": { "version": "2.1.3" }
+            }
+        }"#;
+        let packages = parse_package_lock(lock, "/app/package-lock.json");
+
+        assert_eq!(names(&packages), [("debug", "4.3.4"), ("ms", "2.1.2"), ("ms", "2.1.3")]);
+        assert!(parse_package_lock(b"not json", "/app/package-lock.json").is_empty());
+    }
+
+    #[test]
+    fn parses_cargo_lock() {
+        let lock = br#"
+version = 3
+
+[[package]]
+name = "serde"
+version = "1.0.197"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "abc"
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["serde"]
+"#;
+        let packages = parse_cargo_lock(lock, "/src/Cargo.lock");
+
+        assert_eq!(names(&packages), [("serde", "1.0.197"), ("app", "0.1.0")]);
+        assert_eq!(packages[0].purl, "pkg:cargo/serde@1.0.197");
+        assert!(parse_cargo_lock(b"[[package]\n", "/src/Cargo.lock").is_empty());
+    }
+
+    #[test]
+    fn parses_requirements() {
+        let requirements = "\
+# comment
+requests==2.31.0  # pinned
+Django>=4.2
+uvicorn[standard]==0.29.0
+pywin32==306 ; sys_platform == 'win32'
+numpy===1.26.4
+-r other.txt
+--index-url https://example.com/simple
+git+https://github.com/org/pkg.git#egg=pkg
+";
+        let packages = parse_requirements(requirements, "/app/requirements.txt");
+
+        assert_eq!(names(&packages), [
+            ("requests", "2.31.0"),
+            ("Django", ""),
+            ("uvicorn", "0.29.0"),
+            ("pywin32", "306"),
+            ("numpy", "1.26.4"),
+        ]);
+        assert_eq!(packages[1].purl, "pkg:pypi/django");
+    }
+
+    #[test]
+    fn parses_python_metadata() {
+        let metadata = "\
+Metadata-Version: 2.1
+Name: typing_extensions
+Version: 4.11.0
+License: Python Software Foundation License
+
+Long description body: not a header
+";
+        let found = parse_python_metadata(metadata, "/site-packages/typing_extensions-4.11.0.dist-info/METADATA").unwrap();
+        assert_eq!((found.name.as_str(), found.version.as_str()), ("typing_extensions", "4.11.0"));
+        assert_eq!(found.purl, "pkg:pypi/typing-extensions@4.11.0");
+        assert_eq!(found.license.as_deref(), Some("Python Software Foundation License"));
+
+        // License-Expression wins, full license texts are dropped.
+        let found = parse_python_metadata("Name: a\nVersion: 1\nLicense: MIT\nLicense-Expression: MIT OR Apache-2.0\n", "/m").unwrap();
+        assert_eq!(found.license.as_deref(), Some("MIT OR Apache-2.0"));
+        let found = parse_python_metadata("Name: a\nVersion: 1\nLicense: Copyright (c)\n all rights\n", "/m").unwrap();
+        assert_eq!(found.license, None);
+
+        assert!(parse_python_metadata("Name: missing-version\n", "/m").is_none());
+    }
+
+    #[test]
+    fn parses_go_build_info() {
+        let modinfo = "path\texample.com/cmd\nmod\texample.com/cmd\t(devel)\t\n\
+                       dep\tgolang.org/x/text\tv0.14.0\th1:abc=\n\
+                       dep\tgithub.com/old/lib\tv1.0.0\n\
+                       =>\tgithub.com/fork/lib\tv1.0.1\th1:def=\n";
+        let sentinel = [0u8; 16];
+
+        let mut binary = b"\x7fELF padding".to_vec();
+        let mut header = GO_BUILDINFO_MAGIC.to_vec();
+        header.push(8);
+        header.push(0x2);
+        header.resize(32, 0);
+        binary.extend(header);
+
+        let go_version = b"go1.22.3 X:nocoverageredesign";
+        binary.push(go_version.len() as u8);
+        binary.extend_from_slice(go_version);
+
+        let mut wrapped = sentinel.to_vec();
+        wrapped.extend_from_slice(modinfo.as_bytes());
+        wrapped.extend_from_slice(&sentinel);
+        // Longer than 127 bytes, so the length takes two varint bytes.
+        assert!(wrapped.len() > 127);
+        binary.push((wrapped.len() as u8 & 0x7f) | 0x80);
+        binary.push((wrapped.len() >> 7) as u8);
+        binary.extend(wrapped);
+
+        let packages = go_build_info(&binary, "/usr/bin/app").unwrap();
+        assert_eq!(names(&packages), [
+            ("stdlib", "1.22.3"),
+            ("example.com/cmd", "(devel)"),
+            ("golang.org/x/text", "v0.14.0"),
+            ("github.com/fork/lib", "v1.0.1"),
+        ]);
+        assert_eq!(packages[2].purl, "pkg:golang/golang.org/x/text@v0.14.0");
+
+        assert!(go_build_info(b"\x7fELF no build info", "/usr/bin/app").is_none());
+    }
+
+    #[test]
+    fn deduplicates_on_purl() {
+        let files = BTreeMap::from([
+            ("etc/os-release".to_string(), Captured::Text(b"ID=debian\nVERSION_ID=\"12\"\n".to_vec())),
+            ("app/requirements.txt".to_string(), Captured::Text(b"typing_extensions==4.11.0\n".to_vec())),
+            (
+                "usr/lib/python3/dist-packages/typing_extensions-4.11.0.dist-info/METADATA".to_string(),
+                Captured::Text(b"Name: typing-extensions\nVersion: 4.11.0\nLicense: PSF-2.0\n".to_vec()),
+            ),
+        ]);
+
+        let (os, packages) = parse_captured(files).unwrap();
+
+        assert_eq!(os.unwrap().version_id, "12");
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].purl, "pkg:pypi/typing-extensions@4.11.0");
+        assert_eq!(packages[0].license.as_deref(), Some("PSF-2.0"));
+        assert_eq!(packages[0].locations.len(), 2);
+    }
+}
@@ -0,0 +1,449 @@
+use reqwest::header::{ACCEPT, CONTENT_TYPE, LINK, WWW_AUTHENTICATE};
+use reqwest::{Client, Method, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::credentials::{split_registry, DOCKER_HUB_REGISTRY};
+
+const DOCKER_CONTENT_DIGEST: &str = "Docker-Content-Digest";
+
+// Manifest lists/indexes first, so tags resolve to the digest `docker pull` records.
+pub const MANIFEST_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+
+#[derive(Clone, Debug)]
+pub struct RegistryAuth {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CatalogResponse {
+    #[serde(default)]
+    repositories: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TagsResponse {
+    // `null` once every tag of a repository was deleted
+    tags: Option<Vec<String>>,
+}
+
+// A fetched manifest, its body is left to the caller since the schema depends on the media type.
+pub struct RawManifest {
+    pub digest: String,
+    pub media_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+enum Challenge {
+    Basic,
+    Bearer(HashMap<String, String>),
+}
+
+// Registry v2 HTTP client handling both basic auth and the bearer token flow.
+pub struct RegistryClient {
+    http: Client,
+    base_url: String,
+    auth: Option<RegistryAuth>,
+    tokens: Mutex<HashMap<String, String>>,
+}
+
+// Docker treats localhost registries as insecure, so do we.
+pub fn registry_base_url(registry: &str) -> String {
+    let host = registry.split(':').next().unwrap_or(registry);
+
+    if registry == DOCKER_HUB_REGISTRY {
+        "https://registry-1.docker.io".to_string()
+    } else if host == "localhost" || host == "127.0.0.1" || host == "::1" {
+        format!("http://{}", registry)
+    } else {
+        format!("https://{}", registry)
+    }
+}
+
+// `nginx` -> (docker.io, library/nginx, latest), `ghcr.io/org/app@sha256:...` -> (ghcr.io, org/app, sha256:...)
+pub fn parse_reference(reference: &str) -> (String, String, String) {
+    let (registry, remainder) = split_registry(reference);
+
+    let (name, tag) = match remainder.split_once('@') {
+        Some((name, digest)) => (name, digest.to_string()),
+        None => match remainder.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') => (name, tag.to_string()),
+            _ => (remainder, "latest".to_string()),
+        },
+    };
+
+    let repository = normalize_repository(&registry, name);
+
+    (registry, repository, tag)
+}
+
+// Official Docker Hub images live under `library/`.
+pub fn normalize_repository(registry: &str, name: &str) -> String {
+    if registry == DOCKER_HUB_REGISTRY && !name.contains('/') {
+        format!("library/{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+// `</v2/_catalog?last=b&n=100>; rel="next"` -> `/v2/_catalog?last=b&n=100`
+fn next_link(header: &str) -> Option<String> {
+    header.split(',')
+        .find(|link| link.contains("rel=\"next\""))
+        .and_then(|link| {
+            let start = link.find('<')? + 1;
+            let end = link.find('>')?;
+            Some(link[start..end].to_string())
+        })
+}
+
+// Links may be relative or absolute, but must stay on the registry the credentials were sent to.
+fn resolve_next(current: &Url, link: &str) -> Result<Url, String> {
+    let next = current.join(link)
+        .map_err(|e| format!("Invalid registry pagination link {}: {}", link, e))?;
+
+    if next.origin() != current.origin() {
+        return Err(format!("Registry pagination link {} points to another host", next));
+    }
+
+    Ok(next)
+}
+
+fn check_status(response: Response, what: &str) -> Result<Response, String> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        StatusCode::NOT_FOUND => Err(format!("{} not found", what)),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(format!("Not authorized to access {}", what)),
+        status => Err(format!("Registry returned {} for {}", status, what)),
+    }
+}
+
+// `Bearer realm="https://auth.docker.io/token",service="registry.docker.io"`
+fn parse_challenge(header: &str) -> Option<Challenge> {
+    let (scheme, params) = header.split_once(' ').unwrap_or((header, ""));
+
+    if scheme.eq_ignore_ascii_case("basic") {
+        return Some(Challenge::Basic);
+    }
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let mut values = HashMap::new();
+    let mut rest = params.trim();
+
+    while let Some((key, after)) = rest.split_once('=') {
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+
+        values.insert(key.trim().to_lowercase(), value.to_string());
+        rest = remaining.trim_start_matches(',').trim();
+    }
+
+    Some(Challenge::Bearer(values))
+}
+
+impl RegistryClient {
+    pub fn new(registry: &str, auth: Option<RegistryAuth>) -> Result<Self, String> {
+        let http = Client::builder()
+            .user_agent(concat!("dockpit/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            http,
+            base_url: registry_base_url(registry),
+            auth,
+            tokens: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn fetch_token(&self, params: &HashMap<String, String>, scope: Option<&str>) -> Result<String, String> {
+        let realm = params.get("realm")
+            .ok_or_else(|| "Registry auth challenge has no realm".to_string())?;
+
+        let mut query: Vec<(&str, &str)> = Vec::new();
+        if let Some(service) = params.get("service") {
+            query.push(("service", service));
+        }
+        if let Some(scope) = scope.or(params.get("scope").map(String::as_str)) {
+            query.push(("scope", scope));
+        }
+        if let Some(ref auth) = self.auth {
+            query.push(("account", &auth.username));
+        }
+
+        let mut request = self.http.get(realm).query(&query);
+        if let Some(ref auth) = self.auth {
+            request = request.basic_auth(&auth.username, Some(&auth.password));
+        }
+
+        let response = request.send().await
+            .map_err(|e| format!("Failed to reach registry auth server: {}", e))?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err("Invalid registry credentials".to_string());
+        }
+
+        let body: TokenResponse = response.error_for_status()
+            .map_err(|e| format!("Registry auth failed: {}", e))?
+            .json().await
+            .map_err(|e| format!("Invalid registry auth response: {}", e))?;
+
+        body.token.or(body.access_token)
+            .ok_or_else(|| "Registry auth response has no token".to_string())
+    }
+
+    // Sends a request, answering a 401 challenge once with basic auth or a bearer token
+    // for `scope` (e.g. `repository:library/nginx:pull`).
+    pub async fn request(&self, method: Method, path: &str, scope: Option<&str>, accept: &[&str]) -> Result<Response, String> {
+        let url = Url::parse(&self.base_url)
+            .and_then(|base| base.join(path))
+            .map_err(|e| format!("Invalid registry URL {}: {}", path, e))?;
+        let scope_key = scope.unwrap_or_default().to_string();
+
+        let build = |token: Option<&str>, basic: bool| {
+            let mut request = self.http.request(method.clone(), url.clone());
+            for value in accept {
+                request = request.header(ACCEPT, *value);
+            }
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            } else if basic {
+                if let Some(ref auth) = self.auth {
+                    request = request.basic_auth(&auth.username, Some(&auth.password));
+                }
+            }
+            request
+        };
+
+        let cached = self.tokens.lock().unwrap().get(&scope_key).cloned();
+        let response = build(cached.as_deref(), false).send().await
+            .map_err(|e| format!("Failed to reach registry: {}", e))?;
+
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response.headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_challenge);
+
+        let response = match challenge {
+            Some(Challenge::Basic) => build(None, true).send().await,
+            Some(Challenge::Bearer(params)) => {
+                let token = self.fetch_token(&params, scope).await?;
+                self.tokens.lock().unwrap().insert(scope_key, token.clone());
+                build(Some(&token), false).send().await
+            },
+            None => return Ok(response),
+        }
+        .map_err(|e| format!("Failed to reach registry: {}", e))?;
+
+        Ok(response)
+    }
+
+    pub async fn manifest_digest(&self, repository: &str, reference: &str) -> Result<String, String> {
+        let path = format!("/v2/{}/manifests/{}", repository, reference);
+        let scope = format!("repository:{}:pull", repository);
+
+        let response = self.request(Method::HEAD, &path, Some(&scope), MANIFEST_MEDIA_TYPES).await?;
+        match response.status() {
+            status if status.is_success() => {},
+            StatusCode::NOT_FOUND => return Err(format!("Manifest {}:{} not found", repository, reference)),
+            StatusCode::UNAUTHORIZED => return Err(format!("Not authorized to read {}", repository)),
+            status => return Err(format!("Registry returned {}", status)),
+        }
+
+        if let Some(digest) = response.headers().get(DOCKER_CONTENT_DIGEST).and_then(|v| v.to_str().ok()) {
+            return Ok(digest.to_string());
+        }
+
+        // Some registries omit the header on HEAD, the digest is the hash of the manifest body.
+        let body = self.request(Method::GET, &path, Some(&scope), MANIFEST_MEDIA_TYPES).await?
+            .error_for_status()
+            .map_err(|e| format!("Registry returned {}", e))?
+            .bytes().await
+            .map_err(|e| format!("Failed to read manifest: {}", e))?;
+
+        Ok(format!("sha256:{:x}", Sha256::digest(&body)))
+    }
+
+    // `path` is relative to the registry or an absolute URL from a previous page's `Link` header.
+    async fn get_json<T: DeserializeOwned>(&self, path: &str, scope: &str, what: &str) -> Result<(T, Option<Url>), String> {
+        let response = check_status(self.request(Method::GET, path, Some(scope), &[]).await?, what)?;
+        let next = response.headers().get(LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link)
+            .map(|link| resolve_next(response.url(), &link))
+            .transpose()?;
+
+        let body = response.json().await
+            .map_err(|e| format!("Invalid registry response for {}: {}", what, e))?;
+
+        Ok((body, next))
+    }
+
+    // One page of `/v2/_catalog`, with the `last` cursor for the following page.
+    pub async fn catalog(&self, last: Option<&str>, limit: u32) -> Result<(Vec<String>, Option<String>), String> {
+        let mut params = vec![("n", limit.to_string())];
+        if let Some(last) = last {
+            params.push(("last", last.to_string()));
+        }
+        let query = Url::parse_with_params("http://registry/v2/_catalog", &params)
+            .map_err(|e| format!("Invalid catalog cursor: {}", e))?;
+        let path = format!("/v2/_catalog?{}", query.query().unwrap_or_default());
+
+        let (body, next): (CatalogResponse, _) = self.get_json(&path, "registry:catalog:*", "catalog").await?;
+
+        let cursor = next
+            .and_then(|url| url.query_pairs().find(|(key, _)| key == "last").map(|(_, value)| value.into_owned()));
+
+        Ok((body.repositories, cursor))
+    }
+
+    pub async fn tags(&self, repository: &str) -> Result<Vec<String>, String> {
+        let scope = format!("repository:{}:pull", repository);
+        let mut path = Some(format!("/v2/{}/tags/list", repository));
+        let mut tags = Vec::new();
+
+        while let Some(current) = path {
+            let (body, next): (TagsResponse, _) = self.get_json(&current, &scope, repository).await?;
+            tags.extend(body.tags.unwrap_or_default());
+            path = next.map(String::from);
+        }
+
+        Ok(tags)
+    }
+
+    pub async fn manifest(&self, repository: &str, reference: &str) -> Result<RawManifest, String> {
+        let path = format!("/v2/{}/manifests/{}", repository, reference);
+        let scope = format!("repository:{}:pull", repository);
+        let what = format!("manifest {}:{}", repository, reference);
+
+        let response = check_status(self.request(Method::GET, &path, Some(&scope), MANIFEST_MEDIA_TYPES).await?, &what)?;
+
+        let headers = response.headers();
+        let digest = headers.get(DOCKER_CONTENT_DIGEST)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let media_type = headers.get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_string());
+
+        let body = response.bytes().await
+            .map_err(|e| format!("Failed to read manifest: {}", e))?
+            .to_vec();
+
+        Ok(RawManifest {
+            digest: digest.unwrap_or_else(|| format!("sha256:{:x}", Sha256::digest(&body))),
+            media_type,
+            body,
+        })
+    }
+
+    pub async fn blob_json<T: DeserializeOwned>(&self, repository: &str, digest: &str) -> Result<T, String> {
+        let path = format!("/v2/{}/blobs/{}", repository, digest);
+        let scope = format!("repository:{}:pull", repository);
+
+        let (body, _) = self.get_json(&path, &scope, digest).await?;
+        Ok(body)
+    }
+
+    // The registry only deletes by digest, tags have to be resolved first.
+    pub async fn delete_manifest(&self, repository: &str, digest: &str) -> Result<(), String> {
+        let path = format!("/v2/{}/manifests/{}", repository, digest);
+        let scope = format!("repository:{}:delete", repository);
+
+        let response = self.request(Method::DELETE, &path, Some(&scope), MANIFEST_MEDIA_TYPES).await?;
+        match response.status() {
+            StatusCode::METHOD_NOT_ALLOWED => Err("Deleting is disabled on this registry".to_string()),
+            _ => check_status(response, &format!("manifest {}@{}", repository, digest)).map(|_| ()),
+        }
+    }
+
+    pub async fn check_login(&self) -> Result<(), String> {
+        let response = self.request(Method::GET, "/v2/", None, &[]).await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::UNAUTHORIZED => Err("Invalid registry credentials".to_string()),
+            status => Err(format!("Registry returned {}", status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_next_link() {
+        let header = r#"</v2/_catalog?last=b&n=100>; rel="next""#;
+        assert_eq!(next_link(header).as_deref(), Some("/v2/_catalog?last=b&n=100"));
+
+        let header = r#"<https://r.example/v2/a/tags/list?n=1&last=x>; rel="prev", <https://r.example/v2/a/tags/list?n=1&last=y>; rel="next""#;
+        assert_eq!(next_link(header).as_deref(), Some("https://r.example/v2/a/tags/list?n=1&last=y"));
+
+        assert_eq!(next_link(r#"</v2/_catalog?last=a>; rel="prev""#), None);
+    }
+
+    #[test]
+    fn resolves_next_against_current_url() {
+        let current = Url::parse("https://r.example/v2/org/app/tags/list?n=50").unwrap();
+
+        let relative = resolve_next(&current, "/v2/org/app/tags/list?n=50&last=v1").unwrap();
+        assert_eq!(relative.as_str(), "https://r.example/v2/org/app/tags/list?n=50&last=v1");
+
+        let absolute = resolve_next(&current, "https://r.example/v2/org/app/tags/list?last=v2").unwrap();
+        assert_eq!(absolute.as_str(), "https://r.example/v2/org/app/tags/list?last=v2");
+
+        assert!(resolve_next(&current, "https://elsewhere.example/v2/org/app/tags/list").is_err());
+        assert!(resolve_next(&current, "http://r.example/v2/org/app/tags/list").is_err());
+    }
+
+    #[test]
+    fn parses_references() {
+        assert_eq!(
+            parse_reference("nginx"),
+            ("docker.io".to_string(), "library/nginx".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            parse_reference("localhost:5000/app:1.2"),
+            ("localhost:5000".to_string(), "app".to_string(), "1.2".to_string())
+        );
+        assert_eq!(
+            parse_reference("ghcr.io/org/app@sha256:abc"),
+            ("ghcr.io".to_string(), "org/app".to_string(), "sha256:abc".to_string())
+        );
+        assert_eq!(
+            parse_reference("index.docker.io/redis:7"),
+            ("docker.io".to_string(), "library/redis".to_string(), "7".to_string())
+        );
+        assert_eq!(normalize_repository("docker.io", "redis"), "library/redis");
+        assert_eq!(normalize_repository("docker.io", "bitnami/redis"), "bitnami/redis");
+        assert_eq!(normalize_repository("ghcr.io", "redis"), "redis");
+    }
+}
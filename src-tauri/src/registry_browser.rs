@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::credentials::{normalize_registry, registry_auth_for};
+use crate::registry::{normalize_repository, RegistryClient};
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+const INDEX_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+];
+
+// buildx stores provenance/SBOM attestations as extra index entries.
+const REFERENCE_TYPE_ANNOTATION: &str = "vnd.docker.reference.type";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistryRepositoryPage {
+    pub registry: String,
+    pub repositories: Vec<String>,
+    // Pass back as `last` to fetch the following page
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistryLayer {
+    pub digest: String,
+    pub media_type: String,
+    pub size: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistryPlatformManifest {
+    pub digest: String,
+    pub media_type: String,
+    pub platform: String,
+    pub size: Option<i64>,
+    pub attestation: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegistryManifest {
+    pub repository: String,
+    pub reference: String,
+    pub digest: String,
+    pub media_type: String,
+    pub is_index: bool,
+    // Compressed size: config plus layers for an image, the sum of all platforms for an index
+    pub size: i64,
+    pub platform: Option<String>,
+    pub created: Option<String>,
+    pub config_digest: Option<String>,
+    pub layers: Vec<RegistryLayer>,
+    pub manifests: Vec<RegistryPlatformManifest>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ManifestBody {
+    media_type: Option<String>,
+    config: Option<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: Option<String>,
+    digest: String,
+    #[serde(default)]
+    size: i64,
+    platform: Option<Platform>,
+    annotations: Option<HashMap<String, String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Platform {
+    os: String,
+    architecture: String,
+    variant: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ImageConfigBlob {
+    created: Option<String>,
+    os: Option<String>,
+    architecture: Option<String>,
+    variant: Option<String>,
+}
+
+async fn client_for(registry: &str) -> Result<(String, RegistryClient), String> {
+    let registry = normalize_registry(registry.trim());
+    let client = RegistryClient::new(&registry, registry_auth_for(&registry).await)?;
+    Ok((registry, client))
+}
+
+// `linux/arm64/v8`
+fn platform_name(os: &str, architecture: &str, variant: Option<&str>) -> String {
+    match variant {
+        Some(variant) if !variant.is_empty() => format!("{}/{}/{}", os, architecture, variant),
+        _ => format!("{}/{}", os, architecture),
+    }
+}
+
+fn parse_manifest(body: &[u8]) -> Result<ManifestBody, String> {
+    let value: serde_json::Value = serde_json::from_slice(body)
+        .map_err(|e| format!("Invalid manifest: {}", e))?;
+
+    if value.get("schemaVersion").and_then(|v| v.as_i64()) == Some(1) {
+        return Err("Schema 1 manifests are not supported".to_string());
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid manifest: {}", e))
+}
+
+fn image_size(manifest: &ManifestBody) -> i64 {
+    manifest.config.as_ref().map(|c| c.size).unwrap_or(0)
+        + manifest.layers.iter().map(|l| l.size).sum::<i64>()
+}
+
+#[tauri::command]
+pub async fn list_registry_repositories(
+    registry: String,
+    last: Option<String>,
+    limit: Option<u32>
+) -> Result<RegistryRepositoryPage, String> {
+    let (registry, client) = client_for(&registry).await?;
+
+    let (repositories, next) = client.catalog(last.as_deref(), limit.unwrap_or(DEFAULT_PAGE_SIZE)).await?;
+
+    Ok(RegistryRepositoryPage {
+        registry,
+        repositories,
+        next,
+    })
+}
+
+#[tauri::command]
+pub async fn list_registry_tags(
+    registry: String,
+    repository: String
+) -> Result<Vec<String>, String> {
+    let (registry, client) = client_for(&registry).await?;
+    let repository = normalize_repository(&registry, repository.trim());
+
+    let mut tags = client.tags(&repository).await?;
+    tags.sort();
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub async fn get_registry_manifest(
+    registry: String,
+    repository: String,
+    reference: String
+) -> Result<RegistryManifest, String> {
+    let (registry, client) = client_for(&registry).await?;
+    let repository = normalize_repository(&registry, repository.trim());
+
+    let raw = client.manifest(&repository, &reference).await?;
+    let body = parse_manifest(&raw.body)?;
+
+    let media_type = body.media_type.clone()
+        .or(raw.media_type)
+        .unwrap_or_default();
+    let is_index = INDEX_MEDIA_TYPES.contains(&media_type.as_str())
+        || (body.config.is_none() && !body.manifests.is_empty());
+
+    let mut result = RegistryManifest {
+        repository: repository.clone(),
+        reference,
+        digest: raw.digest,
+        media_type,
+        is_index,
+        size: 0,
+        platform: None,
+        created: None,
+        config_digest: None,
+        layers: Vec::new(),
+        manifests: Vec::new(),
+    };
+
+    if is_index {
+        for entry in body.manifests {
+            let attestation = entry.annotations.as_ref()
+                .is_some_and(|a| a.contains_key(REFERENCE_TYPE_ANNOTATION));
+
+            // Sizes need the per-platform manifests, a missing one should not hide the others.
+            let size = match client.manifest(&repository, &entry.digest).await {
+                Ok(child) => parse_manifest(&child.body).ok().map(|m| image_size(&m)),
+                Err(_) => None,
+            };
+            result.size += size.unwrap_or(0);
+
+            result.manifests.push(RegistryPlatformManifest {
+                platform: entry.platform
+                    .map(|p| platform_name(&p.os, &p.architecture, p.variant.as_deref()))
+                    .unwrap_or_else(|| "unknown".to_string()),
+                digest: entry.digest,
+                media_type: entry.media_type.unwrap_or_default(),
+                size,
+                attestation,
+            });
+        }
+
+        return Ok(result);
+    }
+
+    result.size = image_size(&body);
+    result.layers = body.layers.into_iter()
+        .map(|layer| RegistryLayer {
+            digest: layer.digest,
+            media_type: layer.media_type.unwrap_or_default(),
+            size: layer.size,
+        })
+        .collect();
+
+    if let Some(config) = body.config {
+        if let Ok(blob) = client.blob_json::<ImageConfigBlob>(&repository, &config.digest).await {
+            result.created = blob.created;
+            if let (Some(os), Some(architecture)) = (blob.os, blob.architecture) {
+                result.platform = Some(platform_name(&os, &architecture, blob.variant.as_deref()));
+            }
+        }
+        result.config_digest = Some(config.digest);
+    }
+
+    Ok(result)
+}
+
+// Deleting by digest removes every tag that points at the manifest. The blobs stay
+// until the registry's garbage collector runs.
+#[tauri::command]
+pub async fn delete_registry_manifest(
+    registry: String,
+    repository: String,
+    reference: String
+) -> Result<String, String> {
+    let (registry, client) = client_for(&registry).await?;
+    let repository = normalize_repository(&registry, repository.trim());
+
+    // Tags cannot contain a colon, digests always do (`sha256:...`).
+    let digest = if reference.contains(':') {
+        reference
+    } else {
+        client.manifest_digest(&repository, &reference).await?
+    };
+
+    client.delete_manifest(&repository, &digest).await?;
+
+    Ok(digest)
+}
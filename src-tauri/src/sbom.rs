@@ -0,0 +1,208 @@
+use chrono::{SecondsFormat, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::packages::{collect_image_packages, ImagePackages};
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SbomFormat {
+    Spdx,
+    Cyclonedx,
+}
+
+// Both the SPDX namespace and the CycloneDX serial number must be unique per document.
+fn document_urn() -> String {
+    format!("urn:uuid:{}", Uuid::new_v4())
+}
+
+// Only expressions built from SPDX list ids are valid license fields. Distro metadata is
+// full of `GPLv2+`, `GPL-2` or `BSD`, which go to the comment / license name instead.
+fn spdx_expression(license: &str) -> Option<String> {
+    let expression = spdx::Expression::parse(license.trim()).ok()?;
+
+    let listed = expression.requirements()
+        .all(|r| matches!(r.req.license, spdx::LicenseItem::Spdx { .. }));
+
+    listed.then(|| expression.as_ref().to_string())
+}
+
+fn spdx_document(image: &ImagePackages, created: &str) -> Value {
+    let mut packages = vec![json!({
+        "SPDXID": "SPDXRef-Image",
+        "name": image.name,
+        "versionInfo": image.image_id,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "primaryPackagePurpose": "CONTAINER",
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Image",
+    })];
+
+    if let Some(ref os) = image.os {
+        packages.push(json!({
+            "SPDXID": "SPDXRef-OperatingSystem",
+            "name": os.id,
+            "versionInfo": os.version_id,
+            "description": os.pretty_name,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "primaryPackagePurpose": "OPERATING-SYSTEM",
+        }));
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Image",
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": "SPDXRef-OperatingSystem",
+        }));
+    }
+
+    for (index, package) in image.packages.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}-{}", package.ecosystem, index + 1);
+
+        let mut entry = json!({
+            "SPDXID": id,
+            "name": package.name,
+            "versionInfo": package.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "sourceInfo": format!("acquired package info from {}", package.locations.join(", ")),
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": package.purl,
+            }],
+        });
+
+        if let Some(ref license) = package.license {
+            match spdx_expression(license) {
+                Some(expression) => entry["licenseDeclared"] = json!(expression),
+                None => entry["licenseComments"] = json!(license),
+            }
+        }
+
+        packages.push(entry);
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Image",
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": image.name,
+        "documentNamespace": document_urn(),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: dockpit-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+fn cyclonedx_document(image: &ImagePackages, created: &str) -> Value {
+    let mut components = Vec::new();
+
+    if let Some(ref os) = image.os {
+        components.push(json!({
+            "type": "operating-system",
+            "bom-ref": "os",
+            "name": os.id,
+            "version": os.version_id,
+            "description": os.pretty_name,
+        }));
+    }
+
+    for package in &image.packages {
+        let mut component = json!({
+            "type": "library",
+            "bom-ref": package.purl,
+            "name": package.name,
+            "version": package.version,
+            "purl": package.purl,
+            "properties": package.locations.iter()
+                .map(|location| json!({ "name": "dockpit:package:location", "value": location }))
+                .collect::<Vec<_>>(),
+        });
+
+        if let Some(ref license) = package.license {
+            component["licenses"] = match spdx_expression(license) {
+                Some(expression) => json!([{ "expression": expression }]),
+                None => json!([{ "license": { "name": license } }]),
+            };
+        }
+
+        components.push(component);
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": document_urn(),
+        "version": 1,
+        "metadata": {
+            "timestamp": created,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "dockpit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": {
+                "type": "container",
+                "bom-ref": "image",
+                "name": image.name,
+                "version": image.image_id,
+            },
+        },
+        "components": components,
+    })
+}
+
+#[tauri::command]
+pub async fn generate_sbom(
+    id: String,
+    format: Option<SbomFormat>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<Value, String> {
+    let docker = get_docker_connection(state)?;
+
+    let image = collect_image_packages(&docker, &id).await?;
+    let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    Ok(match format.unwrap_or(SbomFormat::Spdx) {
+        SbomFormat::Spdx => spdx_document(&image, &created),
+        SbomFormat::Cyclonedx => cyclonedx_document(&image, &created),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_spdx_expressions() {
+        assert_eq!(spdx_expression("MIT").as_deref(), Some("MIT"));
+        assert_eq!(spdx_expression(" Apache-2.0 OR MIT ").as_deref(), Some("Apache-2.0 OR MIT"));
+        assert_eq!(spdx_expression("GPL-2.0-or-later WITH Classpath-exception-2.0").as_deref(), Some("GPL-2.0-or-later WITH Classpath-exception-2.0"));
+        assert_eq!(spdx_expression("(MIT AND BSD-3-Clause) OR Zlib").as_deref(), Some("(MIT AND BSD-3-Clause) OR Zlib"));
+    }
+
+    #[test]
+    fn rejects_distro_license_names() {
+        for license in ["GPLv2+", "GPL-2", "BSD", "Artistic", "MIT/X11", "mit or apache-2.0", "LicenseRef-custom", "public domain", ""] {
+            assert_eq!(spdx_expression(license), None, "{}", license);
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use bollard::models::VolumeCreateOptions;
+use bollard::query_parameters::{DownloadFromContainerOptions, UploadToContainerOptions};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tauri::Emitter;
+use tokio::io::AsyncWriteExt;
+
+use crate::image_transfer::{counted_file_stream, partial_path, ArchiveProgressEvent};
+use crate::images::{EmitThrottle, PROGRESS_EMIT_INTERVAL};
+use crate::operations::Operations;
+use crate::volumes::{
+    create_volume_helper,
+    remove_volume_helper,
+    volume_containers,
+    volume_sizes,
+    VOLUME_MOUNT_PATH,
+};
+use crate::{get_docker_connection, DockerConnection};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VolumeBackupResult {
+    pub path: String,
+    pub checksum_path: String,
+    pub checksum: String,
+    pub size: u64,
+    pub compression: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VolumeRestoreResult {
+    pub name: String,
+    pub size: u64,
+    pub checksum: String,
+    // False when neither a checksum nor a `.sha256` file was there to compare against
+    pub verified: bool,
+}
+
+// `backup.tar.gz` -> `backup.tar.gz.sha256`, in `sha256sum` format.
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".sha256");
+    path.with_file_name(name)
+}
+
+async fn file_sha256(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Checksum task failed: {}", e))?
+}
+
+// Backups taken here hold a top-level `volume/` directory (the archive API names entries
+// after the requested path). Plain tarballs of the volume contents go inside the mount.
+async fn restore_target(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut magic = [0u8; 2];
+        let read = file.read(&mut magic)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let reader = std::io::Cursor::new(magic[..read].to_vec()).chain(file);
+
+        let reader: Box<dyn Read> = if magic[..read] == [0x1f, 0x8b] {
+            Box::new(GzDecoder::new(reader))
+        } else {
+            Box::new(reader)
+        };
+
+        let mut archive = tar::Archive::new(reader);
+        let first = archive.entries()
+            .map_err(|e| format!("Invalid archive {}: {}", path.display(), e))?
+            .next()
+            .ok_or_else(|| format!("Archive {} is empty", path.display()))?
+            .map_err(|e| format!("Invalid archive {}: {}", path.display(), e))?
+            .path()
+            .map_err(|e| format!("Invalid archive {}: {}", path.display(), e))?
+            .into_owned();
+
+        let mount_dir = VOLUME_MOUNT_PATH.trim_start_matches('/');
+        Ok(match first.components().next() {
+            Some(Component::Normal(name)) if name == mount_dir => "/".to_string(),
+            _ => VOLUME_MOUNT_PATH.to_string(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Archive task failed: {}", e))?
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn backup_volume(
+    name: String,
+    path: String,
+    compression: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<VolumeBackupResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let compression = compression.unwrap_or_else(|| "gzip".to_string()).to_lowercase();
+    let gzip = match compression.as_str() {
+        "gzip" | "gz" => true,
+        "none" | "" => false,
+        other => return Err(format!("Unsupported compression: {}", other)),
+    };
+
+    // The uncompressed tar is slightly larger than the volume, close enough for progress.
+    let total = volume_sizes(&docker).await
+        .get(&name)
+        .map(|size| *size as u64)
+        .unwrap_or(0);
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let helper = create_volume_helper(&docker, &name, true).await?;
+
+    let output = PathBuf::from(&path);
+    let partial = partial_path(&output);
+
+    let result: Result<(u64, String), String> = async {
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+
+        let options = DownloadFromContainerOptions {
+            path: VOLUME_MOUNT_PATH.to_string(),
+        };
+        let mut archive_stream = docker.download_from_container(&helper, Some(options));
+        let mut encoder = gzip.then(|| GzEncoder::new(Vec::new(), Compression::default()));
+        let mut hasher = Sha256::new();
+        let mut throttle = EmitThrottle::default();
+        let mut read = 0u64;
+        let mut written = 0u64;
+
+        loop {
+            let chunk = tokio::select! {
+                chunk = archive_stream.next() => chunk,
+                _ = &mut cancel_future => return Err("Backup cancelled".to_string()),
+            };
+
+            let Some(chunk) = chunk else {
+                break;
+            };
+
+            let chunk = chunk.map_err(|e| format!("Failed to read volume: {}", e))?;
+            read += chunk.len() as u64;
+
+            // The encoder writes into a Vec that is drained after every chunk.
+            let out = match encoder {
+                Some(ref mut encoder) => {
+                    encoder.write_all(&chunk)
+                        .map_err(|e| format!("Failed to compress backup: {}", e))?;
+                    std::mem::take(encoder.get_mut())
+                },
+                None => chunk.to_vec(),
+            };
+
+            hasher.update(&out);
+            file.write_all(&out)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+            written += out.len() as u64;
+
+            if throttle.ready(false) {
+                let _ = window.emit("volume-backup-progress", ArchiveProgressEvent::new(&operation.id, &path, "Backing up", read, total).estimated());
+            }
+        }
+
+        if let Some(encoder) = encoder {
+            let out = encoder.finish()
+                .map_err(|e| format!("Failed to compress backup: {}", e))?;
+            hasher.update(&out);
+            file.write_all(&out)
+                .await
+                .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+            written += out.len() as u64;
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+
+        Ok((written, format!("{:x}", hasher.finalize())))
+    }.await;
+
+    remove_volume_helper(&docker, &helper).await;
+
+    let (written, checksum) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        },
+    };
+
+    // Re-read what landed on disk, so a backup is only reported once it is known to be intact.
+    let _ = window.emit("volume-backup-progress", ArchiveProgressEvent::new(&operation.id, &path, "Verifying", total, total).estimated());
+    let on_disk = file_sha256(&partial).await?;
+    if on_disk != checksum {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(format!("Backup verification failed: expected {}, got {}", checksum, on_disk));
+    }
+
+    tokio::fs::rename(&partial, &output)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    let sidecar = checksum_path(&output);
+    let file_name = output.file_name().unwrap_or_default().to_string_lossy();
+    tokio::fs::write(&sidecar, format!("{}  {}\n", checksum, file_name))
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", sidecar.display(), e))?;
+
+    window.emit("volume-backup-progress", ArchiveProgressEvent::new(&operation.id, &path, "Backed up", written, written).complete())
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(VolumeBackupResult {
+        path,
+        checksum_path: sidecar.to_string_lossy().to_string(),
+        checksum,
+        size: written,
+        compression: if gzip { "gzip" } else { "none" }.to_string(),
+    })
+}
+
+// Files from the archive are written over the volume's contents, files that are not in
+// the archive are left in place. The volume is created if it does not exist yet.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_volume(
+    name: String,
+    path: String,
+    checksum: Option<String>,
+    operation_id: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, DockerConnection>,
+    operations: tauri::State<'_, Operations>
+) -> Result<VolumeRestoreResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let running: Vec<String> = volume_containers(&docker).await?
+        .remove(&name)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|c| c.state == "running")
+        .map(|c| c.name)
+        .collect();
+    if !running.is_empty() {
+        return Err(format!("Stop the containers using the volume first: {}", running.join(", ")));
+    }
+
+    let archive = PathBuf::from(&path);
+    let expected = match checksum.filter(|c| !c.trim().is_empty()) {
+        Some(checksum) => Some(checksum.trim().to_lowercase()),
+        None => tokio::fs::read_to_string(checksum_path(&archive)).await
+            .ok()
+            .and_then(|content| content.split_whitespace().next().map(str::to_lowercase)),
+    };
+
+    let (operation, cancel_rx) = operations.register(operation_id)?;
+    let mut cancel_future = Box::pin(cancel_rx);
+
+    let _ = window.emit("volume-restore-progress", ArchiveProgressEvent::new(&operation.id, &path, "Verifying", 0, 0));
+    let actual = file_sha256(&archive).await?;
+    if let Some(ref expected) = expected {
+        if *expected != actual {
+            return Err(format!("Checksum mismatch: expected {}, got {}", expected, actual));
+        }
+    }
+
+    let target = restore_target(&archive).await?;
+
+    // Restoring into a new volume is allowed, the helper only mounts existing ones.
+    if docker.inspect_volume(&name).await.is_err() {
+        docker.create_volume(VolumeCreateOptions {
+            name: Some(name.clone()),
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| format!("Failed to create volume: {}", e))?;
+    }
+
+    let helper = create_volume_helper(&docker, &name, false).await?;
+
+    let result: Result<u64, String> = async {
+        // The Engine unpacks gzip, bzip2 and xz itself, so the file is sent as is.
+        let (body, total, sent) = counted_file_stream(&path).await?;
+
+        let options = UploadToContainerOptions {
+            path: target,
+            ..Default::default()
+        };
+        let mut upload = Box::pin(docker.upload_to_container(&helper, Some(options), bollard::body_try_stream(body)));
+        let mut ticker = tokio::time::interval(PROGRESS_EMIT_INTERVAL);
+
+        loop {
+            tokio::select! {
+                result = &mut upload => {
+                    result.map_err(|e| format!("Failed to restore volume: {}", e))?;
+                    break;
+                },
+                _ = ticker.tick() => {
+                    let current = sent.load(Ordering::Relaxed);
+                    let _ = window.emit("volume-restore-progress", ArchiveProgressEvent::new(&operation.id, &path, "Restoring", current, total));
+                },
+                _ = &mut cancel_future => return Err("Restore cancelled".to_string()),
+            }
+        }
+
+        Ok(total)
+    }.await;
+
+    remove_volume_helper(&docker, &helper).await;
+    let size = result?;
+
+    window.emit("volume-restore-progress", ArchiveProgressEvent::new(&operation.id, &path, "Restored", size, size).complete())
+        .map_err(|e| format!("Failed to emit progress: {}", e))?;
+
+    Ok(VolumeRestoreResult {
+        name,
+        size,
+        checksum: actual,
+        verified: expected.is_some(),
+    })
+}
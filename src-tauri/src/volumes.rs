@@ -0,0 +1,357 @@
+use bollard::models::{
+    ContainerCreateBody,
+    HostConfig,
+    Mount,
+    MountPointTypeEnum,
+    MountTypeEnum,
+    VolumeCreateOptions,
+};
+use bollard::query_parameters::{
+    CreateContainerOptions,
+    CreateImageOptions,
+    DataUsageOptions,
+    ListContainersOptionsBuilder,
+    ListVolumesOptions,
+    PruneVolumesOptionsBuilder,
+    RemoveContainerOptionsBuilder,
+    RemoveVolumeOptions,
+};
+use bollard::Docker;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{get_docker_connection, DockerConnection};
+
+pub const DEFAULT_HELPER_IMAGE: &str = "busybox:latest";
+pub const HELPER_LABEL: &str = "dockpit.volume-helper";
+pub const VOLUME_MOUNT_PATH: &str = "/volume";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VolumeContainerRef {
+    pub id: String,
+    pub name: String,
+    pub state: String,
+    pub destination: String,
+    pub rw: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VolumeInfo {
+    pub name: String,
+    pub driver: String,
+    pub mountpoint: String,
+    pub created_at: Option<String>,
+    pub scope: String,
+    pub labels: HashMap<String, String>,
+    pub options: HashMap<String, String>,
+    // None when the driver does not report usage or `system df` failed
+    pub size: Option<i64>,
+    pub containers: Vec<VolumeContainerRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PruneVolumesResult {
+    pub deleted: Vec<String>,
+    pub space_reclaimed: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct VolumeSettings {
+    helper_image: Option<String>,
+}
+
+fn volume_settings_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("dockpit").join("volumes.json"))
+}
+
+fn read_volume_settings() -> VolumeSettings {
+    volume_settings_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_volume_settings(settings: &VolumeSettings) -> Result<(), String> {
+    let path = volume_settings_path()
+        .ok_or_else(|| "Cannot determine config directory".to_string())?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize volume settings: {}", e))?;
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn helper_image() -> String {
+    read_volume_settings().helper_image.unwrap_or_else(|| DEFAULT_HELPER_IMAGE.to_string())
+}
+
+// Sizes are only computed by `system df`, which walks every volume and can be slow.
+pub async fn volume_sizes(docker: &Docker) -> HashMap<String, i64> {
+    let options = DataUsageOptions {
+        _type: Some(vec!["volume".to_string()]),
+    };
+
+    match docker.df(Some(options)).await {
+        Ok(usage) => usage.volumes.unwrap_or_default()
+            .into_iter()
+            .filter_map(|v| {
+                let size = v.usage_data?.size;
+                (size >= 0).then_some((v.name, size))
+            })
+            .collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+async fn pull_helper_image(docker: &Docker, image: &str) -> Result<(), String> {
+    let options = CreateImageOptions {
+        from_image: Some(image.to_string()),
+        ..Default::default()
+    };
+    let mut pull = docker.create_image(Some(options), None, None);
+
+    while let Some(item) = pull.next().await {
+        let error = match item {
+            Ok(info) => info.error_detail.and_then(|d| d.message),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(error) = error {
+            // Typically an air-gapped host, say what is missing and how to provide it.
+            return Err(format!(
+                "Volume access needs the helper image {}, which is not present and could not be pulled ({}). \
+                 Load it with `docker load` or choose an image that is available locally as the volume helper image.",
+                image, error
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// The archive API only works on containers, so volume contents are reached through a
+// helper that mounts the volume at VOLUME_MOUNT_PATH. It is created but never started,
+// so any local image will do.
+pub async fn create_volume_helper(docker: &Docker, volume: &str, read_only: bool) -> Result<String, String> {
+    docker.inspect_volume(volume).await
+        .map_err(|e| format!("Failed to inspect volume: {}", e))?;
+
+    let image = helper_image();
+    if docker.inspect_image(&image).await.is_err() {
+        pull_helper_image(docker, &image).await?;
+    }
+
+    let body = ContainerCreateBody {
+        image: Some(image),
+        cmd: Some(vec!["true".to_string()]),
+        network_disabled: Some(true),
+        labels: Some(HashMap::from([(HELPER_LABEL.to_string(), volume.to_string())])),
+        host_config: Some(HostConfig {
+            mounts: Some(vec![Mount {
+                target: Some(VOLUME_MOUNT_PATH.to_string()),
+                source: Some(volume.to_string()),
+                typ: Some(MountTypeEnum::VOLUME),
+                read_only: Some(read_only),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let response = docker.create_container(None::<CreateContainerOptions>, body).await
+        .map_err(|e| format!("Failed to create helper container: {}", e))?;
+
+    Ok(response.id)
+}
+
+pub async fn remove_volume_helper(docker: &Docker, id: &str) {
+    let options = RemoveContainerOptionsBuilder::default().force(true).build();
+    let _ = docker.remove_container(id, Some(options)).await;
+}
+
+// Every container mounting a volume, keyed by volume name.
+pub async fn volume_containers(docker: &Docker) -> Result<HashMap<String, Vec<VolumeContainerRef>>, String> {
+    let options = ListContainersOptionsBuilder::default()
+        .all(true)
+        .build();
+
+    let containers = docker.list_containers(Some(options)).await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    let mut result: HashMap<String, Vec<VolumeContainerRef>> = HashMap::new();
+    for container in containers {
+        if container.labels.as_ref().is_some_and(|l| l.contains_key(HELPER_LABEL)) {
+            continue;
+        }
+
+        let id = container.id.unwrap_or_default();
+        let name = container.names.unwrap_or_default().first()
+            .map(|n| n.trim_start_matches('/').to_string())
+            .unwrap_or_else(|| id.clone());
+        let state = container.state.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string());
+
+        for mount in container.mounts.unwrap_or_default() {
+            if mount.typ != Some(MountPointTypeEnum::VOLUME) {
+                continue;
+            }
+            let Some(volume) = mount.name else {
+                continue;
+            };
+
+            result.entry(volume).or_default().push(VolumeContainerRef {
+                id: id.chars().take(12).collect(),
+                name: name.clone(),
+                state: state.clone(),
+                destination: mount.destination.unwrap_or_default(),
+                rw: mount.rw.unwrap_or(true),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn get_volumes(
+    state: tauri::State<'_, DockerConnection>
+) -> Result<Vec<VolumeInfo>, String> {
+    let docker = get_docker_connection(state)?;
+
+    let response = docker.list_volumes(None::<ListVolumesOptions>).await
+        .map_err(|e| format!("Failed to list volumes: {}", e))?;
+
+    let sizes = volume_sizes(&docker).await;
+
+    let mut containers = volume_containers(&docker).await?;
+
+    let mut result: Vec<VolumeInfo> = response.volumes.unwrap_or_default()
+        .into_iter()
+        .map(|v| VolumeInfo {
+            size: sizes.get(&v.name).copied(),
+            containers: containers.remove(&v.name).unwrap_or_default(),
+            name: v.name,
+            driver: v.driver,
+            mountpoint: v.mountpoint,
+            created_at: v.created_at,
+            scope: v.scope.map(|s| s.to_string()).unwrap_or_else(|| "local".to_string()),
+            labels: v.labels,
+            options: v.options,
+        })
+        .collect();
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn create_volume(
+    name: Option<String>,
+    driver: Option<String>,
+    driver_opts: Option<HashMap<String, String>>,
+    labels: Option<HashMap<String, String>>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<String, String> {
+    let docker = get_docker_connection(state)?;
+
+    // Without a name the Engine generates one, like `docker volume create` does.
+    let options = VolumeCreateOptions {
+        name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        driver: driver.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        driver_opts,
+        labels,
+        ..Default::default()
+    };
+
+    let volume = docker.create_volume(options).await
+        .map_err(|e| format!("Failed to create volume: {}", e))?;
+
+    Ok(volume.name)
+}
+
+#[tauri::command]
+pub async fn remove_volume(
+    name: String,
+    force: Option<bool>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<(), String> {
+    let docker = get_docker_connection(state)?;
+
+    // The Engine refuses in-use volumes even with force, name the containers instead of ids.
+    let containers = volume_containers(&docker).await?
+        .remove(&name)
+        .unwrap_or_default();
+    if !containers.is_empty() {
+        let names: Vec<String> = containers.into_iter().map(|c| c.name).collect();
+        return Err(format!("Volume is in use by {}", names.join(", ")));
+    }
+
+    let options = RemoveVolumeOptions {
+        force: force.unwrap_or(false),
+    };
+
+    docker.remove_volume(&name, Some(options)).await
+        .map_err(|e| format!("Failed to remove volume: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn prune_volumes(
+    all: Option<bool>,
+    labels: Option<Vec<String>>,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<PruneVolumesResult, String> {
+    let docker = get_docker_connection(state)?;
+
+    let mut filters: HashMap<&str, Vec<String>> = HashMap::new();
+
+    // Since Engine 23 prune only removes anonymous volumes unless `all` is set.
+    if all.unwrap_or(false) {
+        filters.insert("all", vec!["true".to_string()]);
+    }
+
+    // `!key=value` excludes volumes with that label.
+    for label in labels.unwrap_or_default() {
+        match label.strip_prefix('!') {
+            Some(label) => filters.entry("label!").or_default().push(label.to_string()),
+            None => filters.entry("label").or_default().push(label),
+        }
+    }
+
+    let options = PruneVolumesOptionsBuilder::default()
+        .filters(&filters)
+        .build();
+
+    let response = docker.prune_volumes(Some(options)).await
+        .map_err(|e| format!("Failed to prune volumes: {}", e))?;
+
+    Ok(PruneVolumesResult {
+        deleted: response.volumes_deleted.unwrap_or_default(),
+        space_reclaimed: response.space_reclaimed.unwrap_or(0),
+    })
+}
+
+#[tauri::command]
+pub async fn get_volume_helper_image() -> Result<String, String> {
+    Ok(helper_image())
+}
+
+// An empty image resets to the default.
+#[tauri::command]
+pub async fn set_volume_helper_image(
+    image: Option<String>
+) -> Result<String, String> {
+    let mut settings = read_volume_settings();
+    settings.helper_image = image.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+    write_volume_settings(&settings)?;
+
+    Ok(helper_image())
+}
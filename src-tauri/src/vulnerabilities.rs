@@ -0,0 +1,1102 @@
+use chrono::{SecondsFormat, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::packages::{collect_image_packages, ImagePackage, OsRelease};
+use crate::{get_docker_connection, DockerConnection};
+
+// OSV records as published in the per-ecosystem `all.zip` dumps, see ossf.github.io/osv-schema.
+#[derive(Deserialize, Debug)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    upstream: Vec<String>,
+    summary: Option<String>,
+    withdrawn: Option<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OsvSeverity {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    ecosystem_specific: Option<serde_json::Value>,
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RangeEvent {
+    kind: String,
+    version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AffectedRange {
+    kind: String,
+    events: Vec<RangeEvent>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AffectedPackage {
+    ecosystem: String,
+    name: String,
+    ranges: Vec<AffectedRange>,
+    versions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Advisory {
+    id: String,
+    cves: Vec<String>,
+    summary: Option<String>,
+    severity: String,
+    score: Option<f64>,
+    affected: Vec<AffectedPackage>,
+}
+
+// The imported snapshot, kept gzipped in the app data directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct VulnerabilityDatabase {
+    imported_at: String,
+    sources: Vec<String>,
+    advisories: Vec<Advisory>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VulnerabilityDatabaseInfo {
+    pub imported_at: String,
+    pub sources: Vec<String>,
+    pub advisories: usize,
+    pub ecosystems: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VulnerabilityMatch {
+    pub id: String,
+    pub cves: Vec<String>,
+    pub summary: Option<String>,
+    pub severity: String,
+    pub score: Option<f64>,
+    pub package: String,
+    pub version: String,
+    pub ecosystem: String,
+    pub fixed_version: Option<String>,
+    pub locations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageVulnerabilityReport {
+    pub image_id: String,
+    pub name: String,
+    pub os: Option<OsRelease>,
+    pub database_imported_at: String,
+    pub packages_scanned: usize,
+    pub severity_counts: BTreeMap<String, usize>,
+    pub vulnerabilities: Vec<VulnerabilityMatch>,
+}
+
+fn database_path() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join("dockpit").join("vulndb.json.gz"))
+        .ok_or_else(|| "Cannot determine data directory".to_string())
+}
+
+fn load_database() -> Result<Option<VulnerabilityDatabase>, String> {
+    let path = database_path()?;
+    let file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to open {}: {}", path.display(), e)),
+    };
+
+    serde_json::from_reader(std::io::BufReader::new(GzDecoder::new(file)))
+        .map(Some)
+        .map_err(|e| format!("Failed to read vulnerability database: {}", e))
+}
+
+fn save_database(database: &VulnerabilityDatabase) -> Result<(), String> {
+    let path = database_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    // Written aside and renamed, so a failed import keeps the previous snapshot.
+    let partial = path.with_extension("gz.part");
+    let file = std::fs::File::create(&partial)
+        .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+    let mut encoder = GzEncoder::new(std::io::BufWriter::new(file), flate2::Compression::default());
+    serde_json::to_writer(&mut encoder, database)
+        .map_err(|e| format!("Failed to write vulnerability database: {}", e))?;
+    encoder.finish()
+        .map_err(|e| format!("Failed to write vulnerability database: {}", e))?;
+
+    std::fs::rename(&partial, &path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn database_info(database: &VulnerabilityDatabase) -> VulnerabilityDatabaseInfo {
+    let mut ecosystems: BTreeMap<String, usize> = BTreeMap::new();
+    for advisory in &database.advisories {
+        let names: HashSet<&str> = advisory.affected.iter()
+            .map(|a| a.ecosystem.split(':').next().unwrap_or(&a.ecosystem))
+            .collect();
+        for name in names {
+            *ecosystems.entry(name.to_string()).or_default() += 1;
+        }
+    }
+
+    VulnerabilityDatabaseInfo {
+        imported_at: database.imported_at.clone(),
+        sources: database.sources.clone(),
+        advisories: database.advisories.len(),
+        ecosystems,
+    }
+}
+
+fn cvss_v3_score(vector: &str) -> Option<f64> {
+    let metrics: HashMap<&str, &str> = vector.split('/')
+        .filter_map(|part| part.split_once(':'))
+        .collect();
+
+    let changed = *metrics.get("S")? == "C";
+    let av = match *metrics.get("AV")? { "N" => 0.85, "A" => 0.62, "L" => 0.55, "P" => 0.2, _ => return None };
+    let ac = match *metrics.get("AC")? { "L" => 0.77, "H" => 0.44, _ => return None };
+    let pr = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? { "N" => 0.85, "R" => 0.62, _ => return None };
+    let cia = |key: &str| match metrics.get(key).copied() {
+        Some("H") => Some(0.56),
+        Some("L") => Some(0.22),
+        Some("N") => Some(0.0),
+        _ => None,
+    };
+
+    let iss = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let base = if changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+
+    // The spec's Roundup, which avoids floating point artifacts like 4.000001 -> 4.1.
+    let scaled = (base * 100_000.0).round() as i64;
+    Some(if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        (scaled / 10_000 + 1) as f64 / 10.0
+    })
+}
+
+fn rating_for_score(score: f64) -> &'static str {
+    match score {
+        s if s >= 9.0 => "CRITICAL",
+        s if s >= 7.0 => "HIGH",
+        s if s >= 4.0 => "MEDIUM",
+        s if s > 0.0 => "LOW",
+        _ => "NONE",
+    }
+}
+
+// GHSA says MODERATE, Debian reports urgencies like `low**` or `unimportant`.
+fn normalize_severity(label: &str) -> Option<&'static str> {
+    let label = label.trim().trim_end_matches('*').to_lowercase();
+    match label.as_str() {
+        "critical" => Some("CRITICAL"),
+        "high" | "important" => Some("HIGH"),
+        "medium" | "moderate" => Some("MEDIUM"),
+        "low" | "negligible" | "unimportant" => Some("LOW"),
+        _ => None,
+    }
+}
+
+fn record_severity(record: &OsvRecord) -> (String, Option<f64>) {
+    let vectors = record.severity.iter()
+        .chain(record.affected.iter().flat_map(|a| a.severity.iter()));
+    for severity in vectors {
+        if severity.kind == "CVSS_V3" {
+            if let Some(score) = cvss_v3_score(&severity.score) {
+                return (rating_for_score(score).to_string(), Some(score));
+            }
+        }
+    }
+
+    let labels = record.database_specific.iter()
+        .chain(record.affected.iter().flat_map(|a| a.ecosystem_specific.iter().chain(a.database_specific.iter())))
+        .flat_map(|value| [value["severity"].as_str(), value["urgency"].as_str()])
+        .flatten()
+        .chain(record.severity.iter().filter(|s| !s.kind.starts_with("CVSS")).map(|s| s.score.as_str()));
+
+    for label in labels {
+        if let Some(severity) = normalize_severity(label) {
+            return (severity.to_string(), None);
+        }
+    }
+
+    ("UNKNOWN".to_string(), None)
+}
+
+fn convert_record(record: OsvRecord) -> Option<Advisory> {
+    if record.withdrawn.is_some() {
+        return None;
+    }
+
+    let (severity, score) = record_severity(&record);
+
+    let mut cves: Vec<String> = std::iter::once(&record.id)
+        .chain(record.aliases.iter())
+        .chain(record.upstream.iter())
+        .filter_map(|id| id.find("CVE-").map(|at| id[at..].to_string()))
+        .collect();
+    cves.sort();
+    cves.dedup();
+
+    let affected: Vec<AffectedPackage> = record.affected.into_iter()
+        .filter_map(|affected| {
+            let package = affected.package?;
+            let ranges = affected.ranges.into_iter()
+                .filter(|range| range.kind != "GIT")
+                .map(|range| AffectedRange {
+                    kind: range.kind,
+                    events: range.events.into_iter()
+                        .flat_map(|event| event.into_iter())
+                        .map(|(kind, version)| RangeEvent { kind, version })
+                        .collect(),
+                })
+                .collect();
+
+            Some(AffectedPackage {
+                ecosystem: package.ecosystem,
+                name: package.name,
+                ranges,
+                versions: affected.versions,
+            })
+        })
+        .collect();
+
+    if affected.is_empty() {
+        return None;
+    }
+
+    Some(Advisory {
+        id: record.id,
+        cves,
+        summary: record.summary,
+        severity,
+        score,
+        affected,
+    })
+}
+
+// A file holds one record or, in some mirrors, an array of them.
+fn parse_records(content: &[u8], source: &str, advisories: &mut Vec<Advisory>) -> Result<(), String> {
+    let value: serde_json::Value = serde_json::from_slice(content)
+        .map_err(|e| format!("Invalid OSV JSON in {}: {}", source, e))?;
+
+    let records = match value {
+        serde_json::Value::Array(records) => records,
+        record => vec![record],
+    };
+
+    for record in records {
+        let record: OsvRecord = serde_json::from_value(record)
+            .map_err(|e| format!("Invalid OSV record in {}: {}", source, e))?;
+        advisories.extend(convert_record(record));
+    }
+
+    Ok(())
+}
+
+fn import_zip(path: &Path, advisories: &mut Vec<Advisory>) -> Result<(), String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if !entry.is_file() || !entry.name().ends_with(".json") {
+            continue;
+        }
+
+        let name = format!("{}!{}", path.display(), entry.name());
+        let mut content = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        parse_records(&content, &name, advisories)?;
+    }
+
+    Ok(())
+}
+
+fn import_path(path: &Path, advisories: &mut Vec<Advisory>) -> Result<(), String> {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+
+        for entry in entries {
+            let is_dump = entry.is_dir()
+                || entry.extension().is_some_and(|ext| ext == "json" || ext == "zip");
+            if is_dump {
+                import_path(&entry, advisories)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if path.extension().is_some_and(|ext| ext == "zip") {
+        return import_zip(path, advisories);
+    }
+
+    let content = std::fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_records(&content, &path.display().to_string(), advisories)
+}
+
+fn compare_digits(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+// dpkg's verrevcmp: `~` sorts before everything, even the end of the string, letters
+// sort before other symbols.
+fn dpkg_compare_part(a: &str, b: &str) -> Ordering {
+    let order = |c: Option<u8>| -> i32 {
+        match c {
+            Some(b'~') => -1,
+            None => 0,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => c as i32,
+            Some(c) => c as i32 + 256,
+        }
+    };
+
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let (ac, bc) = (order(a.get(i).copied()), order(b.get(j).copied()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        let start_a = i;
+        while i < a.len() && a[i].is_ascii_digit() {
+            i += 1;
+        }
+        let start_b = j;
+        while j < b.len() && b[j].is_ascii_digit() {
+            j += 1;
+        }
+
+        let digits_a = std::str::from_utf8(&a[start_a..i]).unwrap_or_default();
+        let digits_b = std::str::from_utf8(&b[start_b..j]).unwrap_or_default();
+        let ordering = compare_digits(digits_a, digits_b);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+// `[epoch:]upstream[-revision]`
+fn dpkg_compare(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (u64, String, String) {
+        let (epoch, rest) = match v.split_once(':') {
+            Some((epoch, rest)) if epoch.chars().all(|c| c.is_ascii_digit()) => (epoch.parse().unwrap_or(0), rest),
+            _ => (0, v),
+        };
+        let (upstream, revision) = rest.rsplit_once('-').unwrap_or((rest, ""));
+        (epoch, upstream.to_string(), revision.to_string())
+    };
+
+    let (epoch_a, upstream_a, revision_a) = split(a);
+    let (epoch_b, upstream_b, revision_b) = split(b);
+
+    epoch_a.cmp(&epoch_b)
+        .then_with(|| dpkg_compare_part(&upstream_a, &upstream_b))
+        .then_with(|| dpkg_compare_part(&revision_a, &revision_b))
+}
+
+// rpmvercmp: alternating numeric and alphabetic segments, numbers beat letters,
+// `~` sorts before the end of the string and `^` right after it.
+fn rpm_compare_part(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (mut a, mut b) = (a, b);
+
+    loop {
+        a = a.trim_start_matches(|c: char| !c.is_ascii_alphanumeric() && c != '~' && c != '^');
+        b = b.trim_start_matches(|c: char| !c.is_ascii_alphanumeric() && c != '~' && c != '^');
+
+        match (a.starts_with('~'), b.starts_with('~')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            },
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {},
+        }
+
+        match (a.starts_with('^'), b.starts_with('^')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            },
+            (true, false) => return if b.is_empty() { Ordering::Greater } else { Ordering::Less },
+            (false, true) => return if a.is_empty() { Ordering::Less } else { Ordering::Greater },
+            (false, false) => {},
+        }
+
+        if a.is_empty() || b.is_empty() {
+            break;
+        }
+
+        let numeric = a.starts_with(|c: char| c.is_ascii_digit());
+        let in_segment = |c: char| if numeric { c.is_ascii_digit() } else { c.is_ascii_alphabetic() };
+
+        let end_a = a.find(|c: char| !in_segment(c)).unwrap_or(a.len());
+        let end_b = b.find(|c: char| !in_segment(c)).unwrap_or(b.len());
+        let (segment_a, segment_b) = (&a[..end_a], &b[..end_b]);
+
+        if segment_b.is_empty() {
+            return if numeric { Ordering::Greater } else { Ordering::Less };
+        }
+
+        let ordering = if numeric {
+            compare_digits(segment_a, segment_b)
+        } else {
+            segment_a.cmp(segment_b)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        a = &a[end_a..];
+        b = &b[end_b..];
+    }
+
+    a.len().cmp(&b.len())
+}
+
+// `[epoch:]version-release`
+fn rpm_compare(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (u64, String, String) {
+        let (epoch, rest) = match v.split_once(':') {
+            Some((epoch, rest)) => (epoch.parse().unwrap_or(0), rest),
+            None => (0, v),
+        };
+        let (version, release) = rest.rsplit_once('-').unwrap_or((rest, ""));
+        (epoch, version.to_string(), release.to_string())
+    };
+
+    let (epoch_a, version_a, release_a) = split(a);
+    let (epoch_b, version_b, release_b) = split(b);
+
+    epoch_a.cmp(&epoch_b)
+        .then_with(|| rpm_compare_part(&version_a, &version_b))
+        .then_with(|| {
+            if release_a.is_empty() || release_b.is_empty() {
+                Ordering::Equal
+            } else {
+                rpm_compare_part(&release_a, &release_b)
+            }
+        })
+}
+
+fn semver_compare(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<String>, Option<Vec<String>>) {
+        let v = v.trim_start_matches('v');
+        let v = v.split('+').next().unwrap_or(v);
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.split('.').map(String::from).collect())),
+            None => (v, None),
+        };
+        (core.split('.').map(String::from).collect(), pre)
+    };
+
+    let compare_identifier = |a: &String, b: &String| {
+        let numeric_a = !a.is_empty() && a.chars().all(|c| c.is_ascii_digit());
+        let numeric_b = !b.is_empty() && b.chars().all(|c| c.is_ascii_digit());
+        match (numeric_a, numeric_b) {
+            (true, true) => compare_digits(a, b),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => a.cmp(b),
+        }
+    };
+
+    let (core_a, pre_a) = split(a);
+    let (core_b, pre_b) = split(b);
+
+    for i in 0..core_a.len().max(core_b.len()) {
+        let part_a = core_a.get(i).map(String::as_str).unwrap_or("0");
+        let part_b = core_b.get(i).map(String::as_str).unwrap_or("0");
+        let ordering = compare_digits(part_a, part_b);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (pre_a, pre_b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(pre_a), Some(pre_b)) => {
+            for (x, y) in pre_a.iter().zip(pre_b.iter()) {
+                let ordering = compare_identifier(x, y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            pre_a.len().cmp(&pre_b.len())
+        },
+    }
+}
+
+// Rough ordering for apk and PyPI versions: numbers compare numerically and pre-release
+// words (`alpha`, `rc`, `dev`...) sort before the release while `-r1`, `_p1` or `.post1`
+// sort after it.
+fn natural_compare(a: &str, b: &str) -> Ordering {
+    fn tokens(v: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut current = String::new();
+        for c in v.to_lowercase().chars() {
+            let same_kind = current.chars().next()
+                .is_some_and(|first| first.is_ascii_digit() == c.is_ascii_digit());
+            if (!c.is_ascii_alphanumeric() || !same_kind) && !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if c.is_ascii_alphanumeric() {
+                current.push(c);
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+        tokens
+    }
+
+    fn pre_release_rank(word: &str) -> Option<u8> {
+        match word {
+            "dev" => Some(0),
+            "alpha" | "a" => Some(1),
+            "beta" | "b" => Some(2),
+            "pre" | "c" | "rc" => Some(3),
+            _ => None,
+        }
+    }
+
+    let (tokens_a, tokens_b) = (tokens(a), tokens(b));
+
+    for i in 0..tokens_a.len().max(tokens_b.len()) {
+        let ordering = match (tokens_a.get(i), tokens_b.get(i)) {
+            (Some(x), Some(y)) => {
+                let numeric_x = x.starts_with(|c: char| c.is_ascii_digit());
+                let numeric_y = y.starts_with(|c: char| c.is_ascii_digit());
+                match (numeric_x, numeric_y) {
+                    (true, true) => compare_digits(x, y),
+                    (true, false) => if pre_release_rank(y).is_some() { Ordering::Greater } else { Ordering::Less },
+                    (false, true) => if pre_release_rank(x).is_some() { Ordering::Less } else { Ordering::Greater },
+                    (false, false) => match (pre_release_rank(x), pre_release_rank(y)) {
+                        (Some(rx), Some(ry)) => rx.cmp(&ry),
+                        (Some(_), None) => Ordering::Less,
+                        (None, Some(_)) => Ordering::Greater,
+                        (None, None) => x.cmp(y),
+                    },
+                }
+            },
+            (Some(x), None) => if pre_release_rank(x).is_some() { Ordering::Less } else { Ordering::Greater },
+            (None, Some(y)) => if pre_release_rank(y).is_some() { Ordering::Greater } else { Ordering::Less },
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+fn compare_versions(ecosystem: &str, range_kind: &str, a: &str, b: &str) -> Ordering {
+    if range_kind == "SEMVER" {
+        return semver_compare(a, b);
+    }
+    match ecosystem {
+        "deb" => dpkg_compare(a, b),
+        "rpm" => rpm_compare(a, b),
+        "npm" | "cargo" | "golang" => semver_compare(a, b),
+        _ => natural_compare(a, b),
+    }
+}
+
+fn major_minor(version: &str, parts: usize) -> String {
+    version.split('.').take(parts).collect::<Vec<_>>().join(".")
+}
+
+// The OSV ecosystem a package would be listed under. Distro ecosystems carry the
+// release (`Debian:12`, `Alpine:v3.19`) and are matched as prefixes for variants
+// like `Ubuntu:22.04:LTS`.
+fn osv_ecosystem(package: &ImagePackage, os: Option<&OsRelease>) -> Option<String> {
+    let os_id = os.map(|os| os.id.as_str()).unwrap_or_default();
+    let os_version = os.map(|os| os.version_id.as_str()).unwrap_or_default();
+
+    match package.ecosystem.as_str() {
+        "deb" => match os_id {
+            "debian" => Some(format!("Debian:{}", major_minor(os_version, 1))),
+            "ubuntu" => Some(format!("Ubuntu:{}", os_version)),
+            _ => None,
+        },
+        "apk" => match os_id {
+            "alpine" => Some(format!("Alpine:v{}", major_minor(os_version, 2))),
+            "wolfi" => Some("Wolfi".to_string()),
+            _ => None,
+        },
+        "rpm" => match os_id {
+            "rocky" => Some(format!("Rocky Linux:{}", major_minor(os_version, 1))),
+            "almalinux" => Some(format!("AlmaLinux:{}", major_minor(os_version, 1))),
+            "rhel" => Some("Red Hat".to_string()),
+            "sles" => Some("SUSE".to_string()),
+            id if id.starts_with("opensuse") => Some("openSUSE".to_string()),
+            _ => None,
+        },
+        "npm" => Some("npm".to_string()),
+        "cargo" => Some("crates.io".to_string()),
+        "pypi" => Some("PyPI".to_string()),
+        "golang" => Some("Go".to_string()),
+        _ => None,
+    }
+}
+
+fn match_name(ecosystem: &str, name: &str) -> String {
+    if ecosystem == "pypi" || ecosystem == "PyPI" {
+        name.to_lowercase().replace(['_', '.'], "-")
+    } else {
+        name.to_lowercase()
+    }
+}
+
+// OSV range evaluation: walk the events in version order, `introduced` opens an
+// affected interval and `fixed`/`last_affected` close it.
+fn range_affects(ecosystem: &str, range: &AffectedRange, version: &str) -> (bool, Option<String>) {
+    let compare = |a: &str, b: &str| compare_versions(ecosystem, &range.kind, a, b);
+
+    let mut events: Vec<&RangeEvent> = range.events.iter().collect();
+    events.sort_by(|a, b| match (a.version.as_str(), b.version.as_str()) {
+        ("0", "0") => Ordering::Equal,
+        ("0", _) => Ordering::Less,
+        (_, "0") => Ordering::Greater,
+        (x, y) => compare(x, y),
+    });
+
+    let mut affected = false;
+    for event in &events {
+        match event.kind.as_str() {
+            "introduced" if event.version == "0" || compare(version, &event.version) != Ordering::Less => {
+                affected = true;
+            },
+            "fixed" | "limit" if compare(version, &event.version) != Ordering::Less => {
+                affected = false;
+            },
+            "last_affected" if compare(version, &event.version) == Ordering::Greater => {
+                affected = false;
+            },
+            _ => {},
+        }
+    }
+
+    let fixed = events.iter()
+        .find(|event| event.kind == "fixed" && compare(version, &event.version) == Ordering::Less)
+        .map(|event| event.version.clone());
+
+    (affected, fixed)
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "CRITICAL" => 5,
+        "HIGH" => 4,
+        "MEDIUM" => 3,
+        "LOW" => 2,
+        "NONE" => 1,
+        _ => 0,
+    }
+}
+
+fn match_packages(database: &VulnerabilityDatabase, packages: &[ImagePackage], os: Option<&OsRelease>) -> Vec<VulnerabilityMatch> {
+    let mut by_name: HashMap<String, Vec<(&Advisory, &AffectedPackage)>> = HashMap::new();
+    for advisory in &database.advisories {
+        for affected in &advisory.affected {
+            by_name.entry(match_name(&affected.ecosystem, &affected.name))
+                .or_default()
+                .push((advisory, affected));
+        }
+    }
+
+    let mut matches = Vec::new();
+    let mut seen: HashSet<(String, String, String)> = HashSet::new();
+
+    for package in packages {
+        let Some(ecosystem) = osv_ecosystem(package, os) else {
+            continue;
+        };
+        let prefix = format!("{}:", ecosystem);
+
+        // Distro advisories are filed against the source package.
+        let name = package.source.as_deref().unwrap_or(&package.name);
+        let Some(candidates) = by_name.get(&match_name(&package.ecosystem, name)) else {
+            continue;
+        };
+
+        for (advisory, affected) in candidates {
+            if affected.ecosystem != ecosystem && !affected.ecosystem.starts_with(&prefix) {
+                continue;
+            }
+
+            let mut fixed_version = None;
+            let mut is_affected = affected.versions.iter().any(|v| v == &package.version);
+            for range in &affected.ranges {
+                let (in_range, fixed) = range_affects(&package.ecosystem, range, &package.version);
+                if in_range {
+                    is_affected = true;
+                    fixed_version = fixed_version.or(fixed);
+                }
+            }
+
+            if !is_affected || !seen.insert((advisory.id.clone(), package.name.clone(), package.version.clone())) {
+                continue;
+            }
+
+            matches.push(VulnerabilityMatch {
+                id: advisory.id.clone(),
+                cves: advisory.cves.clone(),
+                summary: advisory.summary.clone(),
+                severity: advisory.severity.clone(),
+                score: advisory.score,
+                package: package.name.clone(),
+                version: package.version.clone(),
+                ecosystem: affected.ecosystem.clone(),
+                fixed_version,
+                locations: package.locations.clone(),
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        severity_rank(&b.severity).cmp(&severity_rank(&a.severity))
+            .then_with(|| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)))
+            .then_with(|| a.id.cmp(&b.id))
+            .then_with(|| a.package.cmp(&b.package))
+    });
+
+    matches
+}
+
+#[tauri::command]
+pub async fn import_vulnerability_db(
+    path: String,
+    replace: Option<bool>
+) -> Result<VulnerabilityDatabaseInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut imported = Vec::new();
+        import_path(Path::new(&path), &mut imported)?;
+
+        if imported.is_empty() {
+            return Err(format!("No OSV advisories found in {}", path));
+        }
+
+        // Importing one ecosystem dump after another adds up, newer records win.
+        let mut database = if replace.unwrap_or(false) {
+            VulnerabilityDatabase::default()
+        } else {
+            load_database()?.unwrap_or_default()
+        };
+
+        let imported_ids: HashSet<String> = imported.iter().map(|a| a.id.clone()).collect();
+        database.advisories.retain(|a| !imported_ids.contains(&a.id));
+        database.advisories.extend(imported);
+
+        if !database.sources.contains(&path) {
+            database.sources.push(path);
+        }
+        database.imported_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        save_database(&database)?;
+        Ok(database_info(&database))
+    })
+    .await
+    .map_err(|e| format!("Failed to import vulnerability database: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_vulnerability_db_info() -> Result<Option<VulnerabilityDatabaseInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| Ok(load_database()?.as_ref().map(database_info)))
+        .await
+        .map_err(|e| format!("Failed to read vulnerability database: {}", e))?
+}
+
+#[tauri::command]
+pub async fn scan_image_vulnerabilities(
+    id: String,
+    state: tauri::State<'_, DockerConnection>
+) -> Result<ImageVulnerabilityReport, String> {
+    let docker = get_docker_connection(state)?;
+
+    let database = tauri::async_runtime::spawn_blocking(load_database)
+        .await
+        .map_err(|e| format!("Failed to read vulnerability database: {}", e))??
+        .ok_or_else(|| "No vulnerability database imported".to_string())?;
+
+    let image = collect_image_packages(&docker, &id).await?;
+    let vulnerabilities = match_packages(&database, &image.packages, image.os.as_ref());
+
+    let mut severity_counts: BTreeMap<String, usize> = BTreeMap::new();
+    for vulnerability in &vulnerabilities {
+        *severity_counts.entry(vulnerability.severity.clone()).or_default() += 1;
+    }
+
+    Ok(ImageVulnerabilityReport {
+        image_id: image.image_id,
+        name: image.name,
+        os: image.os,
+        database_imported_at: database.imported_at,
+        packages_scanned: image.packages.len(),
+        severity_counts,
+        vulnerabilities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ascending(compare: fn(&str, &str) -> Ordering, versions: &[&str]) {
+        for pair in versions.windows(2) {
+            assert_eq!(compare(pair[0], pair[1]), Ordering::Less, "{} < {}", pair[0], pair[1]);
+            assert_eq!(compare(pair[1], pair[0]), Ordering::Greater, "{} > {}", pair[1], pair[0]);
+        }
+    }
+
+    fn range(kind: &str, events: &[(&str, &str)]) -> AffectedRange {
+        AffectedRange {
+            kind: kind.to_string(),
+            events: events.iter()
+                .map(|(kind, version)| RangeEvent {
+                    kind: kind.to_string(),
+                    version: version.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn dpkg_tilde_sorts_before_everything() {
+        // The ordering example from the Debian policy manual.
+        assert_ascending(dpkg_compare, &["1.0~~", "1.0~~a", "1.0~", "1.0", "1.0a"]);
+        assert_ascending(dpkg_compare, &["1.0~rc1", "1.0~rc2", "1.0", "1.0+b1", "1.0.1"]);
+    }
+
+    #[test]
+    fn dpkg_epochs_and_revisions() {
+        assert_eq!(dpkg_compare("1:0.1", "2.0"), Ordering::Greater);
+        assert_eq!(dpkg_compare("0:1.2-3", "1.2-3"), Ordering::Equal);
+        assert_eq!(dpkg_compare("1.0", "1.0-0"), Ordering::Equal);
+        assert_eq!(dpkg_compare("007", "7"), Ordering::Equal);
+        assert_ascending(dpkg_compare, &["0.9.8", "0.10", "2.30-9", "2.30-10"]);
+        assert_ascending(dpkg_compare, &["1.2-1", "1.2-1ubuntu1", "1.2-1ubuntu1.1", "1.2-2"]);
+        // Only the last hyphen starts the revision.
+        assert_eq!(dpkg_compare("1.2-3-4", "1.2-3-5"), Ordering::Less);
+    }
+
+    #[test]
+    fn rpm_matches_rpmvercmp_vectors() {
+        let cases = [
+            ("1.0", "1.0", Ordering::Equal),
+            ("1.0", "2.0", Ordering::Less),
+            ("2.0.1", "2.0", Ordering::Greater),
+            ("2.0.1a", "2.0.1", Ordering::Greater),
+            ("5.5p1", "5.5p2", Ordering::Less),
+            ("5.5p10", "5.5p1", Ordering::Greater),
+            ("10xyz", "10.1xyz", Ordering::Less),
+            ("xyz10", "xyz10.1", Ordering::Less),
+            ("1.0aa", "1.0a", Ordering::Greater),
+            ("6.0.rc1", "6.0", Ordering::Greater),
+            ("1b.fc17", "1.fc17", Ordering::Less),
+            ("2.0", "2_0", Ordering::Equal),
+            ("1.0~rc1", "1.0", Ordering::Less),
+            ("1.0~rc1", "1.0~rc2", Ordering::Less),
+            ("1.0~rc1~git123", "1.0~rc1", Ordering::Less),
+            ("1.0^", "1.0", Ordering::Greater),
+            ("1.0^git1", "1.0", Ordering::Greater),
+            ("1.0^git1", "1.01", Ordering::Less),
+            ("1.0^20160101", "1.0.1", Ordering::Less),
+            ("1.0^20160101^git1", "1.0^20160101", Ordering::Greater),
+            ("1.0~rc1^git1", "1.0~rc1", Ordering::Greater),
+            ("1.0^git1~pre", "1.0^git1", Ordering::Less),
+            ("1.0^git1", "1.0~rc1", Ordering::Greater),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(rpm_compare_part(a, b), expected, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn rpm_epochs_and_releases() {
+        assert_eq!(rpm_compare("1:1.0-1", "2.0-1"), Ordering::Greater);
+        assert_eq!(rpm_compare("1.0-1.el9", "1.0-2.el9"), Ordering::Less);
+        // A version without release matches any release of it.
+        assert_eq!(rpm_compare("1.0", "1.0-5.el9"), Ordering::Equal);
+    }
+
+    #[test]
+    fn semver_precedence() {
+        // The precedence example from semver.org.
+        assert_ascending(semver_compare, &[
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+        ]);
+        assert_eq!(semver_compare("v1.2.3", "1.2.3+build.5"), Ordering::Equal);
+    }
+
+    #[test]
+    fn range_with_fixed_version() {
+        let range = range("ECOSYSTEM", &[("introduced", "0"), ("fixed", "1.2.3-2")]);
+
+        assert_eq!(range_affects("deb", &range, "1.2.3-1"), (true, Some("1.2.3-2".to_string())));
+        assert_eq!(range_affects("deb", &range, "1.2.3~rc1-1"), (true, Some("1.2.3-2".to_string())));
+        assert_eq!(range_affects("deb", &range, "1.2.3-2"), (false, None));
+        assert_eq!(range_affects("deb", &range, "1:1.0-1"), (false, None));
+    }
+
+    #[test]
+    fn range_with_several_intervals() {
+        let range = range("SEMVER", &[
+            ("introduced", "2.0.0"),
+            ("fixed", "2.3.1"),
+            ("introduced", "1.0.0"),
+            ("fixed", "1.4.2"),
+        ]);
+
+        assert!(!range_affects("npm", &range, "0.9.0").0);
+        assert_eq!(range_affects("npm", &range, "1.4.1"), (true, Some("1.4.2".to_string())));
+        assert!(!range_affects("npm", &range, "1.5.0").0);
+        assert_eq!(range_affects("npm", &range, "2.3.0"), (true, Some("2.3.1".to_string())));
+        assert!(range_affects("npm", &range, "2.3.1-rc.1").0);
+        assert!(!range_affects("npm", &range, "2.3.1").0);
+    }
+
+    #[test]
+    fn range_with_last_affected() {
+        let range = range("ECOSYSTEM", &[("introduced", "1.0"), ("last_affected", "1.5")]);
+
+        assert_eq!(range_affects("rpm", &range, "1.5"), (true, None));
+        assert!(!range_affects("rpm", &range, "1.5.1").0);
+        assert!(!range_affects("rpm", &range, "0.9").0);
+    }
+
+    #[test]
+    fn cvss_v3_published_scores() {
+        let cases = [
+            // CVE-2021-44228 (Log4Shell)
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H", 10.0),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+            // CVE-2014-0160 (Heartbleed)
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N", 7.5),
+            ("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H", 7.8),
+            ("CVSS:3.0/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N", 6.1),
+            ("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:N", 5.9),
+            ("CVSS:3.1/AV:P/AC:H/PR:H/UI:R/S:U/C:L/I:N/A:N", 1.6),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N", 0.0),
+        ];
+
+        for (vector, score) in cases {
+            assert_eq!(cvss_v3_score(vector), Some(score), "{}", vector);
+        }
+    }
+
+    #[test]
+    fn cvss_v3_rejects_incomplete_vectors() {
+        assert_eq!(cvss_v3_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"), None);
+        assert_eq!(cvss_v3_score("CVSS:3.1/AV:X/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), None);
+        assert_eq!(rating_for_score(9.8), "CRITICAL");
+        assert_eq!(rating_for_score(6.1), "MEDIUM");
+        assert_eq!(rating_for_score(0.0), "NONE");
+    }
+}
@@ -50,17 +50,49 @@ interface VolumeMount {
   rw: boolean;
 }
 
+interface ContainerNetwork {
+  name: string;
+  network_id: string;
+  ip_address: string | null;
+  ip_prefix_len: number | null;
+  gateway: string | null;
+  ipv6_address: string | null;
+  ipv6_gateway: string | null;
+  mac_address: string | null;
+  aliases: string[];
+}
+
 interface ContainerDetails {
   env: string[];
   ports: PortMapping[];
   volumes: VolumeMount[];
-  networks: string[];
+  networks: ContainerNetwork[];
   hostname: string;
   image_id: string;
   created: string;
   restart_policy: string;
 }
 
+interface StructuredLog {
+  timestamp: string | null;
+  level: string | null;
+  message: string | null;
+  fields: Record<string, string>;
+}
+
+interface LogLine {
+  container_id: string;
+  stream: 'stdout' | 'stderr';
+  timestamp: string | null;
+  level: string | null;
+  line: string;
+  structured: StructuredLog | null;
+}
+
+interface LogEvent extends LogLine {
+  subscription_id: number;
+}
+
 interface ConnectionInfo {
   connection_type: string;
   host: string;
@@ -682,7 +714,7 @@ interface LogsModalProps {
 }
 
 function LogsModal({ containerName, containerId, onClose }: LogsModalProps) {
-  const [logs, setLogs] = useState<string[]>([]);
+  const [logs, setLogs] = useState<LogLine[]>([]);
   const logsEndRef = useRef<HTMLDivElement>(null);
 
   const scrollToBottom = () => {
@@ -690,13 +722,40 @@ function LogsModal({ containerName, containerId, onClose }: LogsModalProps) {
   };
   
   useEffect(() => {
-    invoke("stream_container_logs", { id: containerId }).catch(console.error);
-
-    const unlisten = listen<string>('log-update', (event) => {
-      setLogs(prevLogs => [...prevLogs, event.payload]);
+    let subscriptionId: number | null = null;
+    let cancelled = false;
+    // The stream starts emitting before invoke resolves, so early events are held until the id is known
+    let pending: LogEvent[] = [];
+
+    setLogs([]);
+
+    const unlisten = listen<LogEvent>('log-update', (event) => {
+      if (subscriptionId === null) {
+        pending.push(event.payload);
+      } else if (event.payload.subscription_id === subscriptionId) {
+        setLogs(prevLogs => [...prevLogs, event.payload]);
+      }
     });
 
+    unlisten
+      .then(() => invoke<number>("stream_container_logs", { id: containerId }))
+      .then(id => {
+        subscriptionId = id;
+        const early = pending.filter(e => e.subscription_id === id);
+        pending = [];
+        if (cancelled) {
+          invoke("stop_log_stream", { id }).catch(console.error);
+        } else if (early.length > 0) {
+          setLogs(prevLogs => [...prevLogs, ...early]);
+        }
+      })
+      .catch(console.error);
+
     return () => {
+      cancelled = true;
+      if (subscriptionId !== null) {
+        invoke("stop_log_stream", { id: subscriptionId }).catch(console.error);
+      }
       unlisten.then(f => f());
     };
   }, [containerId]);
@@ -722,9 +781,10 @@ function LogsModal({ containerName, containerId, onClose }: LogsModalProps) {
           {logs.length === 0 ? (
             <div className="text-slate-500 text-center mt-10">Waiting for logs...</div>
           ) : (
-            logs.map((line, index) => (
-              <div key={index} className={line.toLowerCase().includes("error") ? "text-red-400" : ""}>
-                {line}
+            logs.map((log, index) => (
+              <div key={index} className={log.stream === 'stderr' || log.level === 'ERROR' ? "text-red-400" : log.level === 'WARN' ? "text-yellow-400" : ""}>
+                {log.timestamp && <span className="text-slate-500 mr-2">{log.timestamp}</span>}
+                {log.line}
               </div>
             ))
           )}
@@ -986,11 +1046,30 @@ function DetailsModal({ container, onClose }: DetailsModalProps) {
                     {details.networks.length === 0 ? (
                       <p className="text-slate-500 text-sm">No networks connected</p>
                     ) : (
-                      <div className="flex flex-wrap gap-2">
-                        {details.networks.map((network, idx) => (
-                          <span key={idx} className="px-3 py-1 bg-cyan-600/20 text-cyan-400 rounded-full text-sm border border-cyan-500/30">
-                            {network}
-                          </span>
+                      <div className="space-y-2">
+                        {details.networks.map((network) => (
+                          <div key={network.name} className="bg-slate-900 p-3 rounded border border-slate-700">
+                            <span className="px-3 py-1 bg-cyan-600/20 text-cyan-400 rounded-full text-sm border border-cyan-500/30">
+                              {network.name}
+                            </span>
+                            <div className="mt-2 space-y-1 text-sm">
+                              {network.ip_address && (
+                                <p><span className="text-slate-500">IP:</span> <span className="font-mono text-green-400">{network.ip_address}{network.ip_prefix_len ? `/${network.ip_prefix_len}` : ''}</span></p>
+                              )}
+                              {network.gateway && (
+                                <p><span className="text-slate-500">Gateway:</span> <span className="font-mono text-white">{network.gateway}</span></p>
+                              )}
+                              {network.ipv6_address && (
+                                <p><span className="text-slate-500">IPv6:</span> <span className="font-mono text-green-400">{network.ipv6_address}</span></p>
+                              )}
+                              {network.mac_address && (
+                                <p><span className="text-slate-500">MAC:</span> <span className="font-mono text-white">{network.mac_address}</span></p>
+                              )}
+                              {network.aliases.length > 0 && (
+                                <p><span className="text-slate-500">Aliases:</span> <span className="font-mono text-orange-400">{network.aliases.join(', ')}</span></p>
+                              )}
+                            </div>
+                          </div>
                         ))}
                       </div>
                     )}